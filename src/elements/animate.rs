@@ -0,0 +1,47 @@
+use crate::elements::attribute::{Attr, AttributeName, Dur};
+use crate::SvgElement;
+use std::collections::HashMap;
+
+/// SMIL `<animate>` element stepping an attribute through a list of values.
+pub struct Animate {
+    attributes: HashMap<String, String>,
+    value: Option<String>,
+}
+
+impl Animate {
+    pub fn new(attribute_name: AttributeName, values: &[String], dur: Dur) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(attribute_name.name(), attribute_name.value());
+        attributes.insert("values".to_string(), values.join(";"));
+        attributes.insert(dur.name(), dur.value());
+        Self {
+            attributes,
+            value: None,
+        }
+    }
+
+    pub fn values(&self) -> Vec<&str> {
+        self.attributes
+            .get("values")
+            .map(|v| v.split(';').collect())
+            .unwrap_or_default()
+    }
+}
+
+impl SvgElement for Animate {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        "animate"
+    }
+
+    fn value(&self) -> Option<&String> {
+        self.value.as_ref()
+    }
+}
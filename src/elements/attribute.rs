@@ -6,8 +6,10 @@ use crate::elements::value::display::{
 use crate::elements::value::{
     BasicShape, BeginEndValue, ClockValue, GeometryBox, Length, LengthPercentage,
 };
+use crate::UkkoError;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 pub enum Attribute {}
 
@@ -58,6 +60,75 @@ pub trait NumberOptionalNumberAttr {
 
 pub trait SpecialAttr {}
 
+/// Attributes that are also CSS properties, so they can be given in a `style`
+/// declaration as well. Geometry properties and `transform` are left out
+/// since their CSS syntax differs from the attribute.
+pub(crate) const PRESENTATION_ATTRIBUTES: &[&str] = &[
+    "alignment-baseline",
+    "baseline-shift",
+    "clip-path",
+    "clip-rule",
+    "color",
+    "color-interpolation",
+    "color-interpolation-filters",
+    "color-rendering",
+    "cursor",
+    "direction",
+    "display",
+    "dominant-baseline",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "filter",
+    "flood-color",
+    "flood-opacity",
+    "font-family",
+    "font-size",
+    "font-size-adjust",
+    "font-stretch",
+    "font-style",
+    "font-variant",
+    "font-weight",
+    "image-rendering",
+    "letter-spacing",
+    "lighting-color",
+    "marker-end",
+    "marker-mid",
+    "marker-start",
+    "mask",
+    "mask-type",
+    "opacity",
+    "overflow",
+    "paint-order",
+    "pointer-events",
+    "shape-rendering",
+    "stop-color",
+    "stop-opacity",
+    "stroke",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-opacity",
+    "stroke-width",
+    "text-anchor",
+    "text-decoration",
+    "text-overflow",
+    "text-rendering",
+    "transform-origin",
+    "unicode-bidi",
+    "vector-effect",
+    "visibility",
+    "white-space",
+    "word-spacing",
+    "writing-mode",
+];
+
+pub fn is_presentation_attribute(name: &str) -> bool {
+    PRESENTATION_ATTRIBUTES.contains(&name)
+}
+
 pub trait Attr {
     fn name(&self) -> String;
     fn value(&self) -> String;
@@ -70,9 +141,19 @@ pub trait Attr {
         false
     }
 
+    /// Whether the attribute may also be written as a `style` declaration.
+    fn is_presentation(&self) -> bool {
+        is_presentation_attribute(&self.name())
+    }
+
     fn name_value(&self) -> String {
         format!("{}=\"{}\"", self.name(), self.value())
     }
+
+    /// The attribute as a CSS declaration for a `style` attribute.
+    fn declaration(&self) -> String {
+        format!("{}:{}", self.name(), self.value())
+    }
 }
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
@@ -567,7 +648,7 @@ impl Attr for ColorInterpolation {
         .to_string()
     }
 }
-#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ColorInterpolationFilter {
     Auto,
     Srgb,
@@ -577,7 +658,7 @@ pub enum ColorInterpolationFilter {
 
 impl Attr for ColorInterpolationFilter {
     fn name(&self) -> String {
-        "color-interpolation-filter".to_string()
+        "color-interpolation-filters".to_string()
     }
 
     fn value(&self) -> String {
@@ -941,7 +1022,7 @@ impl Attr for Dy {
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EdgeMode {
     Duplicate,
     Wrap,
@@ -992,6 +1073,12 @@ impl Attr for End {
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Exponent(pub f64);
 
+impl Default for Exponent {
+    fn default() -> Self {
+        Exponent(1.)
+    }
+}
+
 impl Attr for Exponent {
     fn name(&self) -> String {
         "exponent".to_string()
@@ -1001,3 +1088,752 @@ impl Attr for Exponent {
         self.0.to_string()
     }
 }
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum StrokeLinecap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+impl EnumAttr for StrokeLinecap {}
+
+impl Attr for StrokeLinecap {
+    fn name(&self) -> String {
+        "stroke-linecap".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            StrokeLinecap::Butt => "butt",
+            StrokeLinecap::Round => "round",
+            StrokeLinecap::Square => "square",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum StrokeLinejoin {
+    /// Joins like `MiterClip` when a path is offset or stroked to a path,
+    /// since flattened segments have no curvature to extend.
+    Arcs,
+    Bevel,
+    #[default]
+    Miter,
+    MiterClip,
+    Round,
+}
+
+impl EnumAttr for StrokeLinejoin {}
+
+impl Attr for StrokeLinejoin {
+    fn name(&self) -> String {
+        "stroke-linejoin".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            StrokeLinejoin::Arcs => "arcs",
+            StrokeLinejoin::Bevel => "bevel",
+            StrokeLinejoin::Miter => "miter",
+            StrokeLinejoin::MiterClip => "miter-clip",
+            StrokeLinejoin::Round => "round",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct StrokeMiterlimit(pub f64);
+
+impl Default for StrokeMiterlimit {
+    fn default() -> Self {
+        StrokeMiterlimit(4.)
+    }
+}
+
+impl FloatAttr for StrokeMiterlimit {
+    fn float_value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Attr for StrokeMiterlimit {
+    fn name(&self) -> String {
+        "stroke-miterlimit".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.rounded_value(None)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum MorphologyOperator {
+    #[default]
+    Erode,
+    Dilate,
+}
+
+impl EnumAttr for MorphologyOperator {}
+
+impl Attr for MorphologyOperator {
+    fn name(&self) -> String {
+        "operator".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            MorphologyOperator::Erode => "erode",
+            MorphologyOperator::Dilate => "dilate",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Radius(pub f64, pub Option<f64>);
+
+impl NumberOptionalNumberAttr for Radius {
+    fn float_value(&self) -> f64 {
+        self.0
+    }
+
+    fn optional_value(&self) -> Option<f64> {
+        self.1
+    }
+}
+
+impl Attr for Radius {
+    fn name(&self) -> String {
+        "radius".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.value_rounded(None)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StdDeviation(pub f64, pub Option<f64>);
+
+impl NumberOptionalNumberAttr for StdDeviation {
+    fn float_value(&self) -> f64 {
+        self.0
+    }
+
+    fn optional_value(&self) -> Option<f64> {
+        self.1
+    }
+}
+
+impl Attr for StdDeviation {
+    fn name(&self) -> String {
+        "stdDeviation".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.value_rounded(None)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct SurfaceScale(pub f64);
+
+impl Default for SurfaceScale {
+    fn default() -> Self {
+        SurfaceScale(1.)
+    }
+}
+
+impl FloatAttr for SurfaceScale {
+    fn float_value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Attr for SurfaceScale {
+    fn name(&self) -> String {
+        "surfaceScale".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.rounded_value(None)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct SpecularConstant(pub f64);
+
+impl Default for SpecularConstant {
+    fn default() -> Self {
+        SpecularConstant(1.)
+    }
+}
+
+impl FloatAttr for SpecularConstant {
+    fn float_value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Attr for SpecularConstant {
+    fn name(&self) -> String {
+        "specularConstant".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.rounded_value(None)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct SpecularExponent(pub f64);
+
+impl Default for SpecularExponent {
+    fn default() -> Self {
+        SpecularExponent(1.)
+    }
+}
+
+impl FloatAttr for SpecularExponent {
+    fn float_value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Attr for SpecularExponent {
+    fn name(&self) -> String {
+        "specularExponent".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.rounded_value(None)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct LimitingConeAngle(pub f64);
+
+/// A cone of 90 degrees lights the whole half-space the spot light faces.
+impl Default for LimitingConeAngle {
+    fn default() -> Self {
+        LimitingConeAngle(90.)
+    }
+}
+
+impl FloatAttr for LimitingConeAngle {
+    fn float_value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Attr for LimitingConeAngle {
+    fn name(&self) -> String {
+        "limitingConeAngle".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.rounded_value(None)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct NumOctaves(pub u32);
+
+impl Default for NumOctaves {
+    fn default() -> Self {
+        NumOctaves(1)
+    }
+}
+
+impl Attr for NumOctaves {
+    fn name(&self) -> String {
+        "numOctaves".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Seed(pub f64);
+
+impl Attr for Seed {
+    fn name(&self) -> String {
+        "seed".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum StitchTiles {
+    #[default]
+    NoStitch,
+    Stitch,
+}
+
+impl EnumAttr for StitchTiles {}
+
+impl Attr for StitchTiles {
+    fn name(&self) -> String {
+        "stitchTiles".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            StitchTiles::NoStitch => "noStitch",
+            StitchTiles::Stitch => "stitch",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum TurbulenceType {
+    FractalNoise,
+    #[default]
+    Turbulence,
+}
+
+impl EnumAttr for TurbulenceType {}
+
+impl Attr for TurbulenceType {
+    fn name(&self) -> String {
+        "type".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            TurbulenceType::FractalNoise => "fractalNoise",
+            TurbulenceType::Turbulence => "turbulence",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Channel {
+    R,
+    G,
+    B,
+    #[default]
+    A,
+}
+
+impl Channel {
+    pub fn index(&self) -> usize {
+        match self {
+            Channel::R => 0,
+            Channel::G => 1,
+            Channel::B => 2,
+            Channel::A => 3,
+        }
+    }
+
+    fn letter(&self) -> String {
+        match self {
+            Channel::R => "R",
+            Channel::G => "G",
+            Channel::B => "B",
+            Channel::A => "A",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct XChannelSelector(pub Channel);
+
+impl EnumAttr for XChannelSelector {}
+
+impl Attr for XChannelSelector {
+    fn name(&self) -> String {
+        "xChannelSelector".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.letter()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct YChannelSelector(pub Channel);
+
+impl EnumAttr for YChannelSelector {}
+
+impl Attr for YChannelSelector {
+    fn name(&self) -> String {
+        "yChannelSelector".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.letter()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Scale(pub f64);
+
+impl Attr for Scale {
+    fn name(&self) -> String {
+        "scale".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Slope(pub f64);
+
+impl Default for Slope {
+    fn default() -> Self {
+        Slope(1.)
+    }
+}
+
+impl Attr for Slope {
+    fn name(&self) -> String {
+        "slope".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Intercept(pub f64);
+
+impl Attr for Intercept {
+    fn name(&self) -> String {
+        "intercept".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Offset(pub f64);
+
+impl Attr for Offset {
+    fn name(&self) -> String {
+        "offset".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TableValues(pub Vec<f64>);
+
+impl Attr for TableValues {
+    fn name(&self) -> String {
+        "tableValues".to_string()
+    }
+
+    fn value(&self) -> String {
+        concat_str_list(&self.0, " ")
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FilterUnits {
+    UserSpaceOnUse,
+    #[default]
+    ObjectBoundingBox,
+}
+
+impl EnumAttr for FilterUnits {}
+
+impl Attr for FilterUnits {
+    fn name(&self) -> String {
+        "filterUnits".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            FilterUnits::UserSpaceOnUse => "userSpaceOnUse",
+            FilterUnits::ObjectBoundingBox => "objectBoundingBox",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum PrimitiveUnits {
+    #[default]
+    UserSpaceOnUse,
+    ObjectBoundingBox,
+}
+
+impl EnumAttr for PrimitiveUnits {}
+
+impl Attr for PrimitiveUnits {
+    fn name(&self) -> String {
+        "primitiveUnits".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            PrimitiveUnits::UserSpaceOnUse => "userSpaceOnUse",
+            PrimitiveUnits::ObjectBoundingBox => "objectBoundingBox",
+        }
+        .to_string()
+    }
+}
+
+/// `operator` of `feComposite`; the arithmetic operator carries `k1` to `k4`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum CompositeOperator {
+    #[default]
+    Over,
+    In,
+    Out,
+    Atop,
+    Xor,
+    Lighter,
+    Arithmetic(f64, f64, f64, f64),
+}
+
+impl EnumAttr for CompositeOperator {}
+
+impl Attr for CompositeOperator {
+    fn name(&self) -> String {
+        "operator".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            CompositeOperator::Over => "over",
+            CompositeOperator::In => "in",
+            CompositeOperator::Out => "out",
+            CompositeOperator::Atop => "atop",
+            CompositeOperator::Xor => "xor",
+            CompositeOperator::Lighter => "lighter",
+            CompositeOperator::Arithmetic(..) => "arithmetic",
+        }
+        .to_string()
+    }
+}
+
+/// `mode` of `feBlend`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 16] = [
+        BlendMode::Normal,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Overlay,
+        BlendMode::Darken,
+        BlendMode::Lighten,
+        BlendMode::ColorDodge,
+        BlendMode::ColorBurn,
+        BlendMode::HardLight,
+        BlendMode::SoftLight,
+        BlendMode::Difference,
+        BlendMode::Exclusion,
+        BlendMode::Hue,
+        BlendMode::Saturation,
+        BlendMode::Color,
+        BlendMode::Luminosity,
+    ];
+
+    /// The CSS keyword of the mode.
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+        }
+    }
+}
+
+impl FromStr for BlendMode {
+    type Err = UkkoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BlendMode::ALL
+            .into_iter()
+            .find(|mode| mode.keyword() == s)
+            .ok_or_else(|| UkkoError::parse(format!("Unknown blend mode `{}`.", s)))
+    }
+}
+
+impl EnumAttr for BlendMode {}
+
+impl Attr for BlendMode {
+    fn name(&self) -> String {
+        "mode".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.keyword().to_string()
+    }
+}
+
+/// CSS `mix-blend-mode`. It is a property without a presentation attribute,
+/// so it belongs in a `style` declaration.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MixBlendMode(pub BlendMode);
+
+impl EnumAttr for MixBlendMode {}
+
+impl Attr for MixBlendMode {
+    fn name(&self) -> String {
+        "mix-blend-mode".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.keyword().to_string()
+    }
+}
+
+/// CSS `isolation`: whether an element groups its descendants for blending.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Isolation {
+    #[default]
+    Auto,
+    Isolate,
+}
+
+impl EnumAttr for Isolation {}
+
+impl Attr for Isolation {
+    fn name(&self) -> String {
+        "isolation".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            Isolation::Auto => "auto",
+            Isolation::Isolate => "isolate",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum GradientUnits {
+    UserSpaceOnUse,
+    #[default]
+    ObjectBoundingBox,
+}
+
+impl EnumAttr for GradientUnits {}
+
+impl Attr for GradientUnits {
+    fn name(&self) -> String {
+        "gradientUnits".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            GradientUnits::UserSpaceOnUse => "userSpaceOnUse",
+            GradientUnits::ObjectBoundingBox => "objectBoundingBox",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SpreadMethod {
+    #[default]
+    Pad,
+    Reflect,
+    Repeat,
+}
+
+impl EnumAttr for SpreadMethod {}
+
+impl Attr for SpreadMethod {
+    fn name(&self) -> String {
+        "spreadMethod".to_string()
+    }
+
+    fn value(&self) -> String {
+        match self {
+            SpreadMethod::Pad => "pad",
+            SpreadMethod::Reflect => "reflect",
+            SpreadMethod::Repeat => "repeat",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StopColor(pub CssColor);
+
+impl Attr for StopColor {
+    fn name(&self) -> String {
+        "stop-color".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StopOpacity(pub f64);
+
+impl Default for StopOpacity {
+    fn default() -> Self {
+        StopOpacity(1.)
+    }
+}
+
+impl Attr for StopOpacity {
+    fn name(&self) -> String {
+        "stop-opacity".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
@@ -0,0 +1,63 @@
+use crate::elements::path::{shapes, PathShape};
+use crate::SvgElement;
+use std::collections::HashMap;
+
+pub struct Circle {
+    center: (f32, f32), // cx, cy
+    radius: f32,        // r
+    attributes: HashMap<String, String>,
+    value: Option<String>,
+}
+
+impl Circle {
+    pub fn new(center: (f32, f32), radius: f32) -> Self {
+        Self {
+            center,
+            radius,
+            attributes: HashMap::new(),
+            value: None,
+        }
+    }
+
+    pub fn center_pos(&self) -> (f32, f32) {
+        self.center
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub fn to_path_shape(&self) -> PathShape {
+        shapes::ellipse(self.center, (self.radius, self.radius))
+    }
+}
+
+impl SvgElement for Circle {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        "circle"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![
+            ("cx".to_string(), Some(self.center.0.to_string())),
+            ("cy".to_string(), Some(self.center.1.to_string())),
+            ("r".to_string(), Some(self.radius.to_string())),
+        ]
+    }
+
+    fn value(&self) -> Option<&String> {
+        self.value.as_ref()
+    }
+
+    fn path_shape(&self) -> Option<PathShape> {
+        Some(self.to_path_shape())
+    }
+}
@@ -0,0 +1,194 @@
+use crate::elements::attribute::{Attr, Channel, ColorInterpolationFilter};
+use crate::elements::filter::FilterPrimitive;
+use crate::filter::component_transfer::{component_transfer, TransferFunction};
+use crate::render::raster::Canvas;
+use crate::{Node, SvgElement};
+use std::collections::HashMap;
+
+/// `feFuncR`, `feFuncG`, `feFuncB` or `feFuncA`, depending on the channel.
+pub struct Func {
+    attributes: HashMap<String, String>,
+    channel: Channel,
+    function: TransferFunction,
+}
+
+impl Func {
+    pub fn new(channel: Channel, function: &TransferFunction) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            channel,
+            function: function.clone(),
+        }
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    pub fn function(&self) -> &TransferFunction {
+        &self.function
+    }
+}
+
+impl SvgElement for Func {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        match self.channel {
+            Channel::R => "feFuncR",
+            Channel::G => "feFuncG",
+            Channel::B => "feFuncB",
+            Channel::A => "feFuncA",
+        }
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let mut attributes = vec![("type".to_string(), Some(self.function.kind().to_string()))];
+        let mut set = |attr: &dyn Attr| attributes.push((attr.name(), Some(attr.value())));
+        match &self.function {
+            TransferFunction::Identity => {}
+            TransferFunction::Table(values) | TransferFunction::Discrete(values) => set(values),
+            TransferFunction::Linear { slope, intercept } => {
+                set(slope);
+                set(intercept);
+            }
+            TransferFunction::Gamma {
+                amplitude,
+                exponent,
+                offset,
+            } => {
+                set(amplitude);
+                set(exponent);
+                set(offset);
+            }
+        }
+        attributes
+    }
+}
+
+/// `<feComponentTransfer>` primitive with one transfer function per channel.
+/// Channels without a function are left unchanged.
+#[derive(Default)]
+pub struct ComponentTransfer {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+    color_interpolation: ColorInterpolationFilter,
+}
+
+impl ComponentTransfer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the function of `channel`, replacing its `feFuncX` child.
+    pub fn with_function(mut self, channel: Channel, function: TransferFunction) -> Self {
+        self.children.retain(|c| {
+            c.downcast_ref::<Func>()
+                .is_some_and(|f| f.channel != channel)
+        });
+        self.children.push(Func::new(channel, &function).into());
+        self
+    }
+
+    pub fn with_color_interpolation(
+        mut self,
+        color_interpolation: ColorInterpolationFilter,
+    ) -> Self {
+        self.color_interpolation = color_interpolation;
+        self
+    }
+
+    pub fn function(&self, channel: Channel) -> &TransferFunction {
+        static IDENTITY: TransferFunction = TransferFunction::Identity;
+        self.children
+            .iter()
+            .filter_map(|c| c.downcast_ref::<Func>())
+            .find(|f| f.channel == channel)
+            .map_or(&IDENTITY, Func::function)
+    }
+
+    pub fn apply(&self, input: &Canvas) -> Canvas {
+        let functions = [Channel::R, Channel::G, Channel::B, Channel::A]
+            .map(|channel| self.function(channel).clone());
+        component_transfer(input, &functions, self.color_interpolation)
+    }
+}
+
+impl SvgElement for ComponentTransfer {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "feComponentTransfer"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let color_interpolation = self.color_interpolation;
+        vec![(
+            color_interpolation.name(),
+            (color_interpolation != ColorInterpolationFilter::default())
+                .then(|| color_interpolation.value()),
+        )]
+    }
+}
+
+impl FilterPrimitive for ComponentTransfer {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::attribute::{Intercept, Slope, TableValues};
+
+    #[test]
+    fn test_functions() {
+        let transfer = ComponentTransfer::new()
+            .with_function(
+                Channel::A,
+                TransferFunction::Table(TableValues(vec![1., 0.])),
+            )
+            .with_function(
+                Channel::R,
+                TransferFunction::Linear {
+                    slope: Slope(0.),
+                    intercept: Intercept(1.),
+                },
+            )
+            .with_function(
+                Channel::A,
+                TransferFunction::Discrete(TableValues(vec![0., 1.])),
+            )
+            .with_color_interpolation(ColorInterpolationFilter::Srgb);
+        let names = transfer
+            .children()
+            .iter()
+            .map(|c| c.as_element().unwrap().name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["feFuncR", "feFuncA"]);
+        assert_eq!(
+            transfer.all_attributes()["color-interpolation-filters"],
+            "sRGB"
+        );
+        let func_a = transfer.children()[1].as_element().unwrap();
+        assert_eq!(func_a.all_attributes()["type"], "discrete");
+        assert_eq!(func_a.all_attributes()["tableValues"], "0 1");
+
+        let mut input = Canvas::new(1, 1);
+        input.data[0] = [0., 0., 0., 0.75];
+        assert_eq!(transfer.apply(&input).data[0], [1., 0., 0., 1.]);
+    }
+}
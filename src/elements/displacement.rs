@@ -0,0 +1,114 @@
+use crate::elements::attribute::{Attr, Scale, XChannelSelector, YChannelSelector};
+use crate::elements::filter::FilterPrimitive;
+use crate::filter::displacement;
+use crate::render::raster::Canvas;
+use crate::SvgElement;
+use std::collections::HashMap;
+
+/// `<feDisplacementMap>` primitive moving the pixels of `in` by the channels
+/// of `in2`.
+#[derive(Default)]
+pub struct DisplacementMap {
+    attributes: HashMap<String, String>,
+    map: displacement::DisplacementMap,
+}
+
+impl DisplacementMap {
+    pub fn new(scale: Scale) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            map: displacement::DisplacementMap::new(scale),
+        }
+    }
+
+    pub fn with_x_channel(mut self, x_channel: XChannelSelector) -> Self {
+        self.map = self.map.with_x_channel(x_channel);
+        self
+    }
+
+    pub fn with_y_channel(mut self, y_channel: YChannelSelector) -> Self {
+        self.map = self.map.with_y_channel(y_channel);
+        self
+    }
+
+    pub fn map(&self) -> &displacement::DisplacementMap {
+        &self.map
+    }
+
+    /// Displaces `input` by `map`, the results of `in` and `in2`.
+    pub fn apply(&self, input: &Canvas, map: &Canvas) -> Canvas {
+        self.map.apply(input, map)
+    }
+}
+
+impl SvgElement for DisplacementMap {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        "feDisplacementMap"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let typed = |attr: &dyn Attr| (attr.name(), Some(attr.value()));
+        vec![
+            typed(&self.map.scale()),
+            typed(&self.map.x_channel()),
+            typed(&self.map.y_channel()),
+        ]
+    }
+}
+
+impl FilterPrimitive for DisplacementMap {
+    fn input_count(&self) -> usize {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::attribute::{BaseFrequency, Channel, TurbulenceType};
+    use crate::elements::filter::{Filter, FilterInput, Primitive};
+    use crate::elements::turbulence::Turbulence;
+
+    #[test]
+    fn test_in_filter() {
+        let noise = Turbulence::new(TurbulenceType::FractalNoise)
+            .with_base_frequency(BaseFrequency(0.05, None))
+            .unwrap();
+        let displace = DisplacementMap::new(Scale(10.))
+            .with_x_channel(XChannelSelector(Channel::R))
+            .with_y_channel(YChannelSelector(Channel::G));
+        assert!(Filter::new()
+            .with_primitive(Primitive::new(DisplacementMap::new(Scale(1.))))
+            .is_err());
+        let filter = Filter::new()
+            .with_primitive(Primitive::new(noise).with_result("noise"))
+            .unwrap()
+            .with_primitive(
+                Primitive::new(displace)
+                    .with_input(FilterInput::SourceGraphic)
+                    .with_input2(FilterInput::Reference("noise".to_string())),
+            )
+            .unwrap();
+        let primitives = filter.primitives();
+        let noise = primitives[0].all_attributes();
+        assert_eq!(primitives[0].name(), "feTurbulence");
+        assert_eq!(noise["baseFrequency"], "0.05");
+        assert_eq!(noise["numOctaves"], "1");
+        assert_eq!(noise["stitchTiles"], "noStitch");
+        assert_eq!(noise["type"], "fractalNoise");
+        let displace = primitives[1].all_attributes();
+        assert_eq!(primitives[1].name(), "feDisplacementMap");
+        assert_eq!(displace["scale"], "10");
+        assert_eq!(displace["xChannelSelector"], "R");
+        assert_eq!(displace["yChannelSelector"], "G");
+        assert_eq!(displace["in2"], "noise");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::elements::path::{shapes, PathShape};
 use crate::SvgElement;
 use std::collections::HashMap;
 
@@ -9,25 +10,55 @@ pub struct Ellipse {
 }
 
 impl Ellipse {
+    pub fn new(center: (f32, f32), radius: (f32, f32)) -> Self {
+        Self {
+            center,
+            radius,
+            attributes: HashMap::new(),
+            value: None,
+        }
+    }
+
     pub fn center_pos(&self) -> (f32, f32) {
         self.center
     }
-}
 
-impl SvgElement for Ellipse {
-    fn children(&self) -> Vec<Box<dyn SvgElement>> {
-        vec![]
+    pub fn radius(&self) -> (f32, f32) {
+        self.radius
     }
 
-    fn attributes(&self) -> HashMap<String, String> {
-        self.attributes.clone()
+    pub fn to_path_shape(&self) -> PathShape {
+        shapes::ellipse(self.center, self.radius)
+    }
+}
+
+impl SvgElement for Ellipse {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
     }
 
     fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
         &mut self.attributes
     }
 
-    fn name(&self) -> String {
-        "ellipse".to_string()
+    fn name(&self) -> &str {
+        "ellipse"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![
+            ("cx".to_string(), Some(self.center.0.to_string())),
+            ("cy".to_string(), Some(self.center.1.to_string())),
+            ("rx".to_string(), Some(self.radius.0.to_string())),
+            ("ry".to_string(), Some(self.radius.1.to_string())),
+        ]
+    }
+
+    fn value(&self) -> Option<&String> {
+        self.value.as_ref()
+    }
+
+    fn path_shape(&self) -> Option<PathShape> {
+        Some(self.to_path_shape())
     }
 }
@@ -0,0 +1,831 @@
+use crate::elements::attribute::{
+    Attr, BlendMode, CompositeOperator, EdgeMode, FilterUnits, MorphologyOperator, PrimitiveUnits,
+    Radius, StdDeviation,
+};
+use crate::elements::value::{Bounds, RegionLength};
+use crate::filter::color_matrix::ColorMatrix;
+use crate::filter::convolve::ConvolveMatrix;
+use crate::{Color, Node, SvgElement, UkkoError, UkkoResult};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Value of an `in` or `in2` attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterInput {
+    SourceGraphic,
+    SourceAlpha,
+    BackgroundImage,
+    BackgroundAlpha,
+    FillPaint,
+    StrokePaint,
+    /// The `result` of an earlier primitive.
+    Reference(String),
+}
+
+impl Display for FilterInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterInput::SourceGraphic => write!(f, "SourceGraphic"),
+            FilterInput::SourceAlpha => write!(f, "SourceAlpha"),
+            FilterInput::BackgroundImage => write!(f, "BackgroundImage"),
+            FilterInput::BackgroundAlpha => write!(f, "BackgroundAlpha"),
+            FilterInput::FillPaint => write!(f, "FillPaint"),
+            FilterInput::StrokePaint => write!(f, "StrokePaint"),
+            FilterInput::Reference(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl FromStr for FilterInput {
+    type Err = UkkoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "" => return Err(UkkoError::parse("Empty filter input.")),
+            "SourceGraphic" => FilterInput::SourceGraphic,
+            "SourceAlpha" => FilterInput::SourceAlpha,
+            "BackgroundImage" => FilterInput::BackgroundImage,
+            "BackgroundAlpha" => FilterInput::BackgroundAlpha,
+            "FillPaint" => FilterInput::FillPaint,
+            "StrokePaint" => FilterInput::StrokePaint,
+            name => FilterInput::Reference(name.to_string()),
+        })
+    }
+}
+
+/// `x`, `y`, `width` and `height` of a filter or primitive; unset values
+/// fall back to their defaults.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Region {
+    pub x: Option<RegionLength>,
+    pub y: Option<RegionLength>,
+    pub width: Option<RegionLength>,
+    pub height: Option<RegionLength>,
+}
+
+impl Region {
+    pub fn new(
+        x: RegionLength,
+        y: RegionLength,
+        width: RegionLength,
+        height: RegionLength,
+    ) -> Self {
+        Self {
+            x: Some(x),
+            y: Some(y),
+            width: Some(width),
+            height: Some(height),
+        }
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        [
+            ("x", self.x),
+            ("y", self.y),
+            ("width", self.width),
+            ("height", self.height),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.map(|v| v.to_string())))
+        .collect()
+    }
+
+    /// Resolves the region, taking unset values from `default`.
+    fn resolve(
+        &self,
+        default: Bounds,
+        bounding_box: Bounds,
+        viewport: (f32, f32),
+        use_bounding_box: bool,
+    ) -> Bounds {
+        let (origin, size) = (
+            bounding_box.0,
+            (
+                bounding_box.1 .0 - bounding_box.0 .0,
+                bounding_box.1 .1 - bounding_box.0 .1,
+            ),
+        );
+        let resolve = |value: Option<RegionLength>, axis: usize, position: bool| {
+            let (origin, size, viewport) = match axis {
+                0 => (origin.0, size.0, viewport.0),
+                _ => (origin.1, size.1, viewport.1),
+            };
+            value.map(|v| {
+                v.resolve(
+                    if position { origin } else { 0. },
+                    size,
+                    viewport,
+                    use_bounding_box,
+                )
+            })
+        };
+        let x = resolve(self.x, 0, true).unwrap_or(default.0 .0);
+        let y = resolve(self.y, 1, true).unwrap_or(default.0 .1);
+        let width = resolve(self.width, 0, false).unwrap_or(default.1 .0 - default.0 .0);
+        let height = resolve(self.height, 1, false).unwrap_or(default.1 .1 - default.0 .1);
+        ((x, y), (x + width, y + height))
+    }
+}
+
+fn intersect(a: Bounds, b: Bounds) -> Bounds {
+    let min = (a.0 .0.max(b.0 .0), a.0 .1.max(b.0 .1));
+    let max = (a.1 .0.min(b.1 .0).max(min.0), a.1 .1.min(b.1 .1).max(min.1));
+    (min, max)
+}
+
+fn union(a: Bounds, b: Bounds) -> Bounds {
+    (
+        (a.0 .0.min(b.0 .0), a.0 .1.min(b.0 .1)),
+        (a.1 .0.max(b.1 .0), a.1 .1.max(b.1 .1)),
+    )
+}
+
+/// A filter primitive element that can be placed in a [`Filter`] through a
+/// [`Primitive`].
+pub trait FilterPrimitive: SvgElement {
+    /// Number of inputs read through `in` and `in2`; generators such as
+    /// `feFlood` read none.
+    fn input_count(&self) -> usize {
+        1
+    }
+
+    /// Inputs read from children instead of `in`, as `feMerge` does.
+    fn child_inputs(&self) -> Vec<&FilterInput> {
+        vec![]
+    }
+
+    /// Whether an unset subregion covers the filter region rather than the
+    /// subregions of the inputs, as for generators and `feTile`.
+    fn fills_filter_region(&self) -> bool {
+        self.input_count() == 0 && self.child_inputs().is_empty()
+    }
+}
+
+/// Operation of a filter primitive defined by its attributes alone.
+#[derive(Clone, Debug)]
+pub enum PrimitiveKind {
+    GaussianBlur {
+        std_deviation: StdDeviation,
+        edge_mode: EdgeMode,
+    },
+    Offset {
+        dx: f64,
+        dy: f64,
+    },
+    Flood {
+        color: Color,
+        opacity: f64,
+    },
+    Composite(CompositeOperator),
+    Blend(BlendMode),
+    Image {
+        href: String,
+    },
+    Tile,
+    DropShadow {
+        dx: f64,
+        dy: f64,
+        std_deviation: StdDeviation,
+        color: Color,
+        opacity: f64,
+    },
+    ColorMatrix(ColorMatrix),
+    ConvolveMatrix(ConvolveMatrix),
+    Morphology {
+        operator: MorphologyOperator,
+        radius: Radius,
+    },
+}
+
+/// Filter primitive element for a [`PrimitiveKind`].
+pub struct BasicPrimitive {
+    attributes: HashMap<String, String>,
+    kind: PrimitiveKind,
+}
+
+impl BasicPrimitive {
+    pub fn new(kind: PrimitiveKind) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            kind,
+        }
+    }
+
+    pub fn kind(&self) -> &PrimitiveKind {
+        &self.kind
+    }
+}
+
+impl SvgElement for BasicPrimitive {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        match self.kind {
+            PrimitiveKind::GaussianBlur { .. } => "feGaussianBlur",
+            PrimitiveKind::Offset { .. } => "feOffset",
+            PrimitiveKind::Flood { .. } => "feFlood",
+            PrimitiveKind::Composite(_) => "feComposite",
+            PrimitiveKind::Blend(_) => "feBlend",
+            PrimitiveKind::Image { .. } => "feImage",
+            PrimitiveKind::Tile => "feTile",
+            PrimitiveKind::DropShadow { .. } => "feDropShadow",
+            PrimitiveKind::ColorMatrix(_) => "feColorMatrix",
+            PrimitiveKind::ConvolveMatrix(_) => "feConvolveMatrix",
+            PrimitiveKind::Morphology { .. } => "feMorphology",
+        }
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let mut attributes = vec![];
+        let mut set = |name: &str, value: String| {
+            attributes.push((name.to_string(), Some(value)));
+        };
+        match &self.kind {
+            PrimitiveKind::GaussianBlur {
+                std_deviation,
+                edge_mode,
+            } => {
+                set("stdDeviation", std_deviation.value());
+                if *edge_mode != EdgeMode::None {
+                    set("edgeMode", edge_mode.value());
+                }
+            }
+            PrimitiveKind::Offset { dx, dy } => {
+                set("dx", dx.to_string());
+                set("dy", dy.to_string());
+            }
+            PrimitiveKind::Flood { color, opacity } => {
+                set("flood-color", color.to_hex_code());
+                set("flood-opacity", opacity.to_string());
+            }
+            PrimitiveKind::Composite(operator) => {
+                set("operator", operator.value());
+                if let CompositeOperator::Arithmetic(k1, k2, k3, k4) = operator {
+                    for (i, k) in [k1, k2, k3, k4].into_iter().enumerate() {
+                        set(&format!("k{}", i + 1), k.to_string());
+                    }
+                }
+            }
+            PrimitiveKind::Blend(mode) => set("mode", mode.value()),
+            PrimitiveKind::Image { href } => set("href", href.clone()),
+            PrimitiveKind::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+                opacity,
+            } => {
+                set("dx", dx.to_string());
+                set("dy", dy.to_string());
+                set("stdDeviation", std_deviation.value());
+                set("flood-color", color.to_hex_code());
+                set("flood-opacity", opacity.to_string());
+            }
+            PrimitiveKind::ColorMatrix(matrix) => {
+                set("type", matrix.kind().to_string());
+                match matrix {
+                    ColorMatrix::Matrix(values) => set(
+                        "values",
+                        values
+                            .iter()
+                            .map(|v| v.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    ),
+                    ColorMatrix::Saturate(v) | ColorMatrix::HueRotate(v) => {
+                        set("values", v.to_string())
+                    }
+                    ColorMatrix::LuminanceToAlpha => {}
+                }
+            }
+            PrimitiveKind::ConvolveMatrix(convolve) => {
+                for (name, value) in convolve.attributes() {
+                    set(&name, value);
+                }
+            }
+            PrimitiveKind::Morphology { operator, radius } => {
+                set("operator", operator.value());
+                set("radius", radius.value());
+            }
+            PrimitiveKind::Tile => {}
+        }
+        attributes
+    }
+}
+
+impl FilterPrimitive for BasicPrimitive {
+    fn input_count(&self) -> usize {
+        match self.kind {
+            PrimitiveKind::Flood { .. } | PrimitiveKind::Image { .. } => 0,
+            PrimitiveKind::Composite(_) | PrimitiveKind::Blend(_) => 2,
+            _ => 1,
+        }
+    }
+
+    fn fills_filter_region(&self) -> bool {
+        matches!(
+            self.kind,
+            PrimitiveKind::Flood { .. } | PrimitiveKind::Image { .. } | PrimitiveKind::Tile
+        )
+    }
+}
+
+/// `<feMergeNode>` naming one input of an `feMerge`.
+pub struct MergeNode {
+    attributes: HashMap<String, String>,
+    input: FilterInput,
+}
+
+impl MergeNode {
+    pub fn new(input: FilterInput) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            input,
+        }
+    }
+
+    pub fn input(&self) -> &FilterInput {
+        &self.input
+    }
+}
+
+impl SvgElement for MergeNode {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        "feMergeNode"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![("in".to_string(), Some(self.input.to_string()))]
+    }
+}
+
+/// `<feMerge>` layering the inputs of its `feMergeNode` children bottom to top.
+pub struct Merge {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+}
+
+impl Merge {
+    pub fn new(inputs: Vec<FilterInput>) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            children: inputs
+                .into_iter()
+                .map(|input| MergeNode::new(input).into())
+                .collect(),
+        }
+    }
+}
+
+impl SvgElement for Merge {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "feMerge"
+    }
+}
+
+impl FilterPrimitive for Merge {
+    fn input_count(&self) -> usize {
+        0
+    }
+
+    fn child_inputs(&self) -> Vec<&FilterInput> {
+        self.children
+            .iter()
+            .filter_map(|c| c.downcast_ref::<MergeNode>())
+            .map(MergeNode::input)
+            .collect()
+    }
+}
+
+/// A filter primitive element wired to other primitives by name.
+pub struct Primitive {
+    element: Box<dyn FilterPrimitive>,
+    input: Option<FilterInput>,
+    input2: Option<FilterInput>,
+    result: Option<String>,
+    subregion: Region,
+}
+
+impl From<PrimitiveKind> for Primitive {
+    fn from(kind: PrimitiveKind) -> Self {
+        Self::new(BasicPrimitive::new(kind))
+    }
+}
+
+impl Primitive {
+    pub fn new(element: impl FilterPrimitive + 'static) -> Self {
+        Self {
+            element: Box::new(element),
+            input: None,
+            input2: None,
+            result: None,
+            subregion: Region::default(),
+        }
+    }
+
+    pub fn with_input(mut self, input: FilterInput) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn with_input2(mut self, input: FilterInput) -> Self {
+        self.input2 = Some(input);
+        self
+    }
+
+    pub fn with_result(mut self, result: impl Into<String>) -> Self {
+        self.result = Some(result.into());
+        self
+    }
+
+    pub fn with_subregion(mut self, subregion: Region) -> Self {
+        self.subregion = subregion;
+        self
+    }
+
+    pub fn element(&self) -> &dyn FilterPrimitive {
+        self.element.as_ref()
+    }
+
+    pub fn input(&self) -> Option<&FilterInput> {
+        self.input.as_ref()
+    }
+
+    pub fn input2(&self) -> Option<&FilterInput> {
+        self.input2.as_ref()
+    }
+
+    pub fn result(&self) -> Option<&str> {
+        self.result.as_deref()
+    }
+
+    /// Every input the primitive reads, explicit or not.
+    fn inputs(&self) -> Vec<Option<&FilterInput>> {
+        let children = self.element.child_inputs();
+        if !children.is_empty() {
+            return children.into_iter().map(Some).collect();
+        }
+        [self.input.as_ref(), self.input2.as_ref()]
+            .into_iter()
+            .take(self.element.input_count())
+            .collect()
+    }
+}
+
+impl SvgElement for Primitive {
+    fn attributes(&self) -> &HashMap<String, String> {
+        self.element.attributes()
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        self.element.attributes_mut()
+    }
+
+    fn children(&self) -> &[Node] {
+        self.element.children()
+    }
+
+    fn name(&self) -> &str {
+        self.element.name()
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let mut attributes = self.element.typed_attributes();
+        attributes.extend([
+            ("in".to_string(), self.input.as_ref().map(|i| i.to_string())),
+            (
+                "in2".to_string(),
+                self.input2.as_ref().map(|i| i.to_string()),
+            ),
+            ("result".to_string(), self.result.clone()),
+        ]);
+        attributes.extend(self.subregion.typed_attributes());
+        attributes
+    }
+}
+
+/// `<filter>` element built from typed primitives. The builder checks that
+/// every named input refers to the result of an earlier primitive.
+#[derive(Default)]
+pub struct Filter {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+    region: Region,
+    filter_units: FilterUnits,
+    primitive_units: PrimitiveUnits,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_region(mut self, region: Region) -> Self {
+        self.region = region;
+        self
+    }
+
+    pub fn with_filter_units(mut self, filter_units: FilterUnits) -> Self {
+        self.filter_units = filter_units;
+        self
+    }
+
+    pub fn with_primitive_units(mut self, primitive_units: PrimitiveUnits) -> Self {
+        self.primitive_units = primitive_units;
+        self
+    }
+
+    /// Appends `primitive`, failing if it reads a result no earlier
+    /// primitive produces or lacks a required `in2`.
+    pub fn with_primitive(mut self, primitive: Primitive) -> UkkoResult<Self> {
+        if primitive.element.input_count() == 2 && primitive.input2.is_none() {
+            return Err(UkkoError::parse(format!(
+                "{} needs an in2 input.",
+                primitive.name()
+            )));
+        }
+        let primitives = self.primitives();
+        for input in primitive.inputs().into_iter().flatten() {
+            if let FilterInput::Reference(name) = input {
+                if find_result(&primitives, name).is_none() {
+                    return Err(UkkoError::parse(format!(
+                        "{} refers to unknown result `{}`.",
+                        primitive.name(),
+                        name
+                    )));
+                }
+            }
+        }
+        self.children.push(primitive.into());
+        Ok(self)
+    }
+
+    pub fn primitives(&self) -> Vec<&Primitive> {
+        self.children
+            .iter()
+            .filter_map(|c| c.downcast_ref::<Primitive>())
+            .collect()
+    }
+
+    /// Filter region in user space for an element with `bounding_box`.
+    /// Unset values default to 10% beyond the bounding box on every side, or
+    /// of the viewport with `userSpaceOnUse`.
+    pub fn region(&self, bounding_box: Bounds, viewport: (f32, f32)) -> Bounds {
+        let percent = RegionLength::Percentage;
+        let region = Region {
+            x: self.region.x.or(Some(percent(-10.))),
+            y: self.region.y.or(Some(percent(-10.))),
+            width: self.region.width.or(Some(percent(120.))),
+            height: self.region.height.or(Some(percent(120.))),
+        };
+        let use_bounding_box = self.filter_units == FilterUnits::ObjectBoundingBox;
+        region.resolve(bounding_box, bounding_box, viewport, use_bounding_box)
+    }
+
+    /// Subregion of each primitive, clipped to the filter region. Unset
+    /// values default to the union of the subregions of the primitives read,
+    /// or the filter region for source inputs, generators and `feTile`.
+    pub fn primitive_subregions(&self, bounding_box: Bounds, viewport: (f32, f32)) -> Vec<Bounds> {
+        let filter_region = self.region(bounding_box, viewport);
+        let use_bounding_box = self.primitive_units == PrimitiveUnits::ObjectBoundingBox;
+        let primitives = self.primitives();
+        let mut subregions: Vec<Bounds> = vec![];
+        for (i, primitive) in primitives.iter().enumerate() {
+            let inputs = primitive.inputs();
+            let default = match primitive.element.fills_filter_region() {
+                true => filter_region,
+                false => inputs
+                    .iter()
+                    .map(|input| match input {
+                        // Implicit inputs read the previous result or the source graphic.
+                        None if i > 0 => subregions[i - 1],
+                        Some(FilterInput::Reference(name)) => find_result(&primitives[..i], name)
+                            .map_or(filter_region, |j| subregions[j]),
+                        _ => filter_region,
+                    })
+                    .reduce(union)
+                    .unwrap_or(filter_region),
+            };
+            let subregion =
+                primitive
+                    .subregion
+                    .resolve(default, bounding_box, viewport, use_bounding_box);
+            subregions.push(intersect(subregion, filter_region));
+        }
+        subregions
+    }
+}
+
+/// Index of the last of `primitives` named `name`.
+fn find_result(primitives: &[&Primitive], name: &str) -> Option<usize> {
+    primitives
+        .iter()
+        .rposition(|p| p.result.as_deref() == Some(name))
+}
+
+impl SvgElement for Filter {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "filter"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let mut attributes = self.region.typed_attributes();
+        let (filter_units, primitive_units) = (self.filter_units, self.primitive_units);
+        attributes.extend([
+            (
+                filter_units.name(),
+                (filter_units != FilterUnits::default()).then(|| filter_units.value()),
+            ),
+            (
+                primitive_units.name(),
+                (primitive_units != PrimitiveUnits::default()).then(|| primitive_units.value()),
+            ),
+        ]);
+        attributes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::attribute::{Azimuth, Elevation};
+    use crate::elements::lighting::DiffuseLighting;
+    use crate::filter::lighting::LightSource;
+
+    fn blur() -> PrimitiveKind {
+        PrimitiveKind::GaussianBlur {
+            std_deviation: StdDeviation(2., None),
+            edge_mode: EdgeMode::None,
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let filter = Filter::new()
+            .with_primitive(Primitive::from(blur()).with_result("blurred"))
+            .unwrap();
+        let unknown = Primitive::from(PrimitiveKind::Offset { dx: 1., dy: 1. })
+            .with_input(FilterInput::Reference("missing".to_string()));
+        assert!(Filter::new().with_primitive(unknown).is_err());
+        let no_in2 = Primitive::from(PrimitiveKind::Blend(BlendMode::Multiply));
+        assert!(Filter::new().with_primitive(no_in2).is_err());
+
+        let filter = filter
+            .with_primitive(
+                Primitive::new(Merge::new(vec![
+                    FilterInput::Reference("blurred".to_string()),
+                    FilterInput::SourceGraphic,
+                ]))
+                .with_result("merged"),
+            )
+            .unwrap();
+        let merge = filter.children()[1].as_element().unwrap();
+        assert_eq!(merge.name(), "feMerge");
+        assert_eq!(merge.children().len(), 2);
+        let node = merge.children()[0].as_element().unwrap();
+        assert_eq!(node.all_attributes()["in"], "blurred");
+        assert_eq!(
+            filter.children()[0].as_element().unwrap().all_attributes()["stdDeviation"],
+            "2"
+        );
+        assert!(filter
+            .with_primitive(Primitive::new(Merge::new(vec![FilterInput::Reference(
+                "later".to_string()
+            )])))
+            .is_err());
+    }
+
+    #[test]
+    fn test_element_primitives() {
+        let light = LightSource::Distant {
+            azimuth: Azimuth(0.),
+            elevation: Elevation(45.),
+        };
+        let convolve = ConvolveMatrix::new((1, 1), vec![2.]).unwrap();
+        let filter = Filter::new()
+            .with_primitive(Primitive::new(DiffuseLighting::new(light)).with_result("lit"))
+            .unwrap()
+            .with_primitive(
+                Primitive::from(PrimitiveKind::ConvolveMatrix(convolve))
+                    .with_input(FilterInput::Reference("lit".to_string())),
+            )
+            .unwrap();
+        let primitives = filter.primitives();
+        assert_eq!(primitives.len(), filter.children().len());
+        assert_eq!(primitives[0].children().len(), 1);
+        assert_eq!(primitives[1].name(), "feConvolveMatrix");
+        assert_eq!(primitives[1].all_attributes()["kernelMatrix"], "2");
+        assert_eq!(primitives[1].all_attributes()["in"], "lit");
+        assert!(primitives[0]
+            .element()
+            .as_any()
+            .downcast_ref::<DiffuseLighting>()
+            .is_some());
+    }
+
+    fn assert_close(a: Bounds, b: Bounds) {
+        let values = |r: Bounds| [r.0 .0, r.0 .1, r.1 .0, r.1 .1];
+        for (a, b) in values(a).into_iter().zip(values(b)) {
+            assert!((a - b).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_regions() {
+        let bounding_box = ((10., 10.), (110., 60.));
+        let viewport = (200., 100.);
+        let filter = Filter::new();
+        assert_close(
+            filter.region(bounding_box, viewport),
+            ((0., 5.), (120., 65.)),
+        );
+        let user_space = Filter::new()
+            .with_filter_units(FilterUnits::UserSpaceOnUse)
+            .with_region(Region {
+                x: Some(RegionLength::Number(5.)),
+                ..Region::default()
+            });
+        assert_close(
+            user_space.region(bounding_box, viewport),
+            ((5., -10.), (245., 110.)),
+        );
+        assert_eq!(user_space.all_attributes()["x"], "5");
+
+        let filter = filter
+            .with_primitive(Primitive::from(blur()).with_result("a"))
+            .unwrap()
+            .with_primitive(
+                Primitive::from(PrimitiveKind::Flood {
+                    color: Color::RED,
+                    opacity: 1.,
+                })
+                .with_subregion(Region {
+                    x: Some(RegionLength::Number(50.)),
+                    width: Some(RegionLength::Number(500.)),
+                    ..Region::default()
+                })
+                .with_result("b"),
+            )
+            .unwrap()
+            .with_primitive(
+                Primitive::from(PrimitiveKind::Composite(CompositeOperator::In))
+                    .with_input(FilterInput::Reference("b".to_string()))
+                    .with_input2(FilterInput::Reference("b".to_string())),
+            )
+            .unwrap();
+        let subregions = filter.primitive_subregions(bounding_box, viewport);
+        assert_close(subregions[0], ((0., 5.), (120., 65.)));
+        // Clipped to the filter region on the right.
+        assert_close(subregions[1], ((50., 5.), (120., 65.)));
+        assert_eq!(subregions[2], subregions[1]);
+
+        let fractions = Filter::new()
+            .with_primitive_units(PrimitiveUnits::ObjectBoundingBox)
+            .with_primitive(Primitive::from(blur()).with_subregion(Region {
+                x: Some(RegionLength::Number(0.5)),
+                ..Region::default()
+            }))
+            .unwrap();
+        let subregions = fractions.primitive_subregions(bounding_box, viewport);
+        assert_close(subregions[0], ((60., 5.), (120., 65.)));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{Node, SvgElement};
+use std::collections::HashMap;
+use xml::attribute::OwnedAttribute;
+use xml::name::OwnedName;
+use xml::namespace::{Namespace, NS_EMPTY_URI, NS_NO_PREFIX, NS_XMLNS_PREFIX, NS_XML_PREFIX};
+use xmltree::{Element, XMLNode};
+
+/// Element of any name, as produced by parsing.
+pub struct GenericElement {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+}
+
+impl GenericElement {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            attributes: HashMap::new(),
+            children: vec![],
+        }
+    }
+
+    pub fn from_xml_element(element: &Element) -> Self {
+        Self::from_xml_element_in(element, None)
+    }
+
+    fn from_xml_element_in(element: &Element, parent: Option<&Namespace>) -> Self {
+        let name = match &element.prefix {
+            Some(prefix) => format!("{}:{}", prefix, element.name),
+            None => element.name.clone(),
+        };
+        let mut attributes = element.attributes.clone();
+        if let Some(namespace) = &element.namespaces {
+            attributes.extend(namespace_declarations(namespace, parent));
+        }
+        let children = element
+            .children
+            .iter()
+            .map(|child| match child {
+                XMLNode::Element(child) => {
+                    Self::from_xml_element_in(child, element.namespaces.as_ref()).into()
+                }
+                child => Node::from_xml_node(child),
+            })
+            .collect();
+        Self {
+            name,
+            attributes,
+            children,
+        }
+    }
+
+    /// Builds an element from a parsed start tag, keeping attribute prefixes and the
+    /// namespaces it declares beyond those of its parent.
+    pub(crate) fn from_start_tag(
+        name: &OwnedName,
+        attributes: &[OwnedAttribute],
+        namespace: &Namespace,
+        parent: Option<&Namespace>,
+    ) -> Self {
+        let mut element = Self::new(&name.borrow().to_repr());
+        element
+            .attributes
+            .extend(namespace_declarations(namespace, parent));
+        element.attributes.extend(
+            attributes
+                .iter()
+                .map(|attribute| (attribute.name.borrow().to_repr(), attribute.value.clone())),
+        );
+        element
+    }
+
+    pub fn with_child(mut self, child: impl Into<Node>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    pub fn children_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.children
+    }
+}
+
+/// The `xmlns` attributes needed to declare `namespace` inside `parent`.
+fn namespace_declarations<'a>(
+    namespace: &'a Namespace,
+    parent: Option<&'a Namespace>,
+) -> impl Iterator<Item = (String, String)> + 'a {
+    namespace
+        .iter()
+        .filter(|(prefix, _)| *prefix != NS_XML_PREFIX && *prefix != NS_XMLNS_PREFIX)
+        .filter(move |(prefix, uri)| {
+            let inherited = match parent {
+                Some(parent) => parent.get(prefix),
+                None if *prefix == NS_NO_PREFIX => Some(NS_EMPTY_URI),
+                None => None,
+            };
+            inherited != Some(*uri)
+        })
+        .map(|(prefix, uri)| match prefix {
+            NS_NO_PREFIX => (NS_XMLNS_PREFIX.to_string(), uri.to_string()),
+            prefix => (format!("{}:{}", NS_XMLNS_PREFIX, prefix), uri.to_string()),
+        })
+}
+
+impl SvgElement for GenericElement {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serializer::{SerializeOptions, Serializer};
+    use crate::Node;
+
+    #[test]
+    fn test_parse_round_trip() {
+        let source = "<?xml-stylesheet href=\"a.css\"?><!-- top -->\
+            <svg><title>A &amp; B</title><style><![CDATA[rect { fill: red; }]]></style>\
+            <text x=\"1\">a<tspan>b</tspan>c</text></svg>";
+        let nodes = Node::parse(source).unwrap();
+        assert!(matches!(&nodes[0], Node::ProcessingInstruction(t, _) if t == "xml-stylesheet"));
+        assert!(matches!(&nodes[1], Node::Comment(c) if c == " top "));
+        let svg = nodes[2].as_element().unwrap();
+        let children = svg.children();
+        assert_eq!(children[0].as_element().unwrap().text_content(), "A & B");
+        assert!(matches!(
+            &children[1].as_element().unwrap().children()[0],
+            Node::CData(_)
+        ));
+        assert_eq!(children[2].as_element().unwrap().text_content(), "abc");
+        let serializer = Serializer::new(SerializeOptions::default());
+        let output = nodes
+            .iter()
+            .map(|n| serializer.serialize_node(&n.to_xml_node()))
+            .collect::<String>();
+        assert_eq!(output, source);
+
+        let source = "<svg xmlns=\"http://www.w3.org/2000/svg\" \
+            xmlns:xlink=\"http://www.w3.org/1999/xlink\"><use xlink:href=\"#a\"/></svg>";
+        let nodes = Node::parse(source).unwrap();
+        let svg = nodes[0].as_element().unwrap();
+        let use_element = svg.children()[0].as_element().unwrap();
+        assert_eq!(use_element.attributes().len(), 1);
+        assert_eq!(use_element.attributes()["xlink:href"], "#a");
+        assert_eq!(serializer.serialize_node(&nodes[0].to_xml_node()), source);
+    }
+}
@@ -0,0 +1,587 @@
+use crate::elements::attribute::{
+    Attr, GradientUnits, Offset, SpreadMethod, StopColor, StopOpacity,
+};
+use crate::elements::value::color::CssColor;
+use crate::elements::value::transform::Transform;
+use crate::elements::value::{Bounds, RegionLength};
+use crate::{Color, Node, SvgElement, UkkoError, UkkoResult};
+use std::collections::HashMap;
+
+/// `<stop>` element of a gradient.
+#[derive(Clone, Debug)]
+pub struct Stop {
+    attributes: HashMap<String, String>,
+    offset: Offset,
+    color: CssColor,
+    opacity: StopOpacity,
+}
+
+impl Stop {
+    /// The offset is clamped between 0 and 1.
+    pub fn new(offset: Offset, color: CssColor) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            offset: Offset(offset.0.clamp(0., 1.)),
+            color,
+            opacity: StopOpacity::default(),
+        }
+    }
+
+    pub fn with_opacity(mut self, opacity: StopOpacity) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    pub fn color(&self) -> &CssColor {
+        &self.color
+    }
+
+    pub fn opacity(&self) -> StopOpacity {
+        self.opacity
+    }
+}
+
+impl SvgElement for Stop {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        "stop"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let color = StopColor(self.color.clone());
+        let opacity = self.opacity;
+        vec![
+            (self.offset.name(), Some(self.offset.value())),
+            (color.name(), Some(color.value())),
+            (
+                opacity.name(),
+                (opacity != StopOpacity::default()).then(|| opacity.value()),
+            ),
+        ]
+    }
+}
+
+/// Gradient vector of a `linearGradient`; unset values are inherited through
+/// `href` or take their defaults.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LinearVector {
+    pub x1: Option<RegionLength>,
+    pub y1: Option<RegionLength>,
+    pub x2: Option<RegionLength>,
+    pub y2: Option<RegionLength>,
+}
+
+/// End circle and focal circle of a `radialGradient`; unset values are
+/// inherited through `href` or take their defaults.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RadialCircles {
+    pub cx: Option<RegionLength>,
+    pub cy: Option<RegionLength>,
+    pub r: Option<RegionLength>,
+    pub fx: Option<RegionLength>,
+    pub fy: Option<RegionLength>,
+    pub fr: Option<RegionLength>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GradientKind {
+    Linear(LinearVector),
+    Radial(RadialCircles),
+}
+
+impl GradientKind {
+    fn values(&self) -> Vec<(&'static str, Option<RegionLength>)> {
+        match *self {
+            GradientKind::Linear(v) => {
+                vec![("x1", v.x1), ("y1", v.y1), ("x2", v.x2), ("y2", v.y2)]
+            }
+            GradientKind::Radial(c) => vec![
+                ("cx", c.cx),
+                ("cy", c.cy),
+                ("r", c.r),
+                ("fx", c.fx),
+                ("fy", c.fy),
+                ("fr", c.fr),
+            ],
+        }
+    }
+
+    /// Fills the unset values from `template` if it is of the same kind.
+    fn inherit(&mut self, template: &GradientKind) {
+        match (self, template) {
+            (GradientKind::Linear(v), GradientKind::Linear(t)) => {
+                v.x1 = v.x1.or(t.x1);
+                v.y1 = v.y1.or(t.y1);
+                v.x2 = v.x2.or(t.x2);
+                v.y2 = v.y2.or(t.y2);
+            }
+            (GradientKind::Radial(c), GradientKind::Radial(t)) => {
+                c.cx = c.cx.or(t.cx);
+                c.cy = c.cy.or(t.cy);
+                c.r = c.r.or(t.r);
+                c.fx = c.fx.or(t.fx);
+                c.fy = c.fy.or(t.fy);
+                c.fr = c.fr.or(t.fr);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `<linearGradient>` or `<radialGradient>` element with `<stop>` children.
+pub struct Gradient {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+    kind: GradientKind,
+    units: Option<GradientUnits>,
+    transform: Option<Transform>,
+    spread_method: Option<SpreadMethod>,
+    href: Option<String>,
+}
+
+impl Gradient {
+    fn new(kind: GradientKind) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            children: vec![],
+            kind,
+            units: None,
+            transform: None,
+            spread_method: None,
+            href: None,
+        }
+    }
+
+    pub fn linear(vector: LinearVector) -> Self {
+        Self::new(GradientKind::Linear(vector))
+    }
+
+    pub fn radial(circles: RadialCircles) -> Self {
+        Self::new(GradientKind::Radial(circles))
+    }
+
+    pub fn with_stop(mut self, stop: Stop) -> Self {
+        self.children.push(stop.into());
+        self
+    }
+
+    pub fn with_units(mut self, units: GradientUnits) -> Self {
+        self.units = Some(units);
+        self
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+
+    pub fn with_spread_method(mut self, spread_method: SpreadMethod) -> Self {
+        self.spread_method = Some(spread_method);
+        self
+    }
+
+    /// Template gradient to inherit unset attributes and stops from, as
+    /// `#id` or `id`.
+    pub fn with_href(mut self, href: impl Into<String>) -> Self {
+        self.href = Some(href.into());
+        self
+    }
+
+    pub fn kind(&self) -> &GradientKind {
+        &self.kind
+    }
+
+    pub fn stops(&self) -> Vec<&Stop> {
+        self.children
+            .iter()
+            .filter_map(|c| c.downcast_ref::<Stop>())
+            .collect()
+    }
+
+    /// Follows the `href` chain through `lookup`, which finds gradients by
+    /// id, and resolves the gradient for an element with `bounding_box` in a
+    /// viewport of `viewport` user units. `currentColor` stops take
+    /// `current_color` and its opacity.
+    pub fn resolve<'a>(
+        &'a self,
+        lookup: impl Fn(&str) -> Option<&'a Gradient>,
+        bounding_box: Bounds,
+        viewport: (f32, f32),
+        current_color: (Color, f32),
+    ) -> UkkoResult<ResolvedGradient> {
+        let mut kind = self.kind;
+        let mut stops = self.stops();
+        let (mut units, mut transform, mut spread_method) =
+            (self.units, self.transform, self.spread_method);
+        let mut visited = vec![];
+        let mut next = self.href.as_deref();
+        while let Some(href) = next {
+            let id = href.trim_start_matches('#');
+            if visited.contains(&id) {
+                return Err(UkkoError::parse(format!(
+                    "Gradient reference cycle through `{}`.",
+                    id
+                )));
+            }
+            visited.push(id);
+            let template = lookup(id)
+                .ok_or_else(|| UkkoError::parse(format!("Unknown gradient `{}`.", id)))?;
+            kind.inherit(&template.kind);
+            if stops.is_empty() {
+                stops = template.stops();
+            }
+            units = units.or(template.units);
+            transform = transform.or(template.transform);
+            spread_method = spread_method.or(template.spread_method);
+            next = template.href.as_deref();
+        }
+
+        let bounding_box_units = units.unwrap_or_default() == GradientUnits::ObjectBoundingBox;
+        let length = |value: Option<RegionLength>, default: f64, reference: f32| match value
+            .unwrap_or(RegionLength::Percentage(default))
+        {
+            RegionLength::Number(n) => n as f32,
+            RegionLength::Percentage(p) if bounding_box_units => p as f32 / 100.,
+            RegionLength::Percentage(p) => p as f32 / 100. * reference,
+        };
+        let diagonal = ((viewport.0.powi(2) + viewport.1.powi(2)) / 2.).sqrt();
+        let geometry = match kind {
+            GradientKind::Linear(v) => Geometry::Linear {
+                start: (length(v.x1, 0., viewport.0), length(v.y1, 0., viewport.1)),
+                end: (length(v.x2, 100., viewport.0), length(v.y2, 0., viewport.1)),
+            },
+            GradientKind::Radial(c) => {
+                let center = (length(c.cx, 50., viewport.0), length(c.cy, 50., viewport.1));
+                Geometry::Radial {
+                    center,
+                    radius: length(c.r, 50., diagonal),
+                    focus: (
+                        c.fx.map_or(center.0, |fx| length(Some(fx), 0., viewport.0)),
+                        c.fy.map_or(center.1, |fy| length(Some(fy), 0., viewport.1)),
+                    ),
+                    focal_radius: length(c.fr, 0., diagonal),
+                }
+            }
+        };
+
+        let mut to_user_space = transform.unwrap_or_default();
+        if bounding_box_units {
+            let (min, max) = bounding_box;
+            to_user_space = Transform::translate(min.0, min.1)
+                * Transform::scale(max.0 - min.0, max.1 - min.1)
+                * to_user_space;
+        }
+        let mut offset = 0.;
+        let stops = stops
+            .iter()
+            .map(|stop| {
+                // Offsets never decrease along the gradient.
+                offset = stop.offset.0.max(offset);
+                let (color, alpha) = stop
+                    .color
+                    .resolve(current_color)
+                    .unwrap_or((Color::BLACK, 1.));
+                (
+                    offset as f32,
+                    color,
+                    alpha * stop.opacity.0.clamp(0., 1.) as f32,
+                )
+            })
+            .collect();
+        Ok(ResolvedGradient {
+            geometry,
+            stops,
+            spread_method: spread_method.unwrap_or_default(),
+            // A degenerate transform leaves nothing to paint.
+            inverse: to_user_space.inverse(),
+        })
+    }
+}
+
+impl SvgElement for Gradient {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        match self.kind {
+            GradientKind::Linear(_) => "linearGradient",
+            GradientKind::Radial(_) => "radialGradient",
+        }
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let mut attributes = self
+            .kind
+            .values()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.map(|v| v.to_string())))
+            .collect::<Vec<_>>();
+        attributes.extend([
+            ("gradientUnits".to_string(), self.units.map(|u| u.value())),
+            (
+                "gradientTransform".to_string(),
+                self.transform.map(|t| t.to_string()),
+            ),
+            (
+                "spreadMethod".to_string(),
+                self.spread_method.map(|s| s.value()),
+            ),
+            ("href".to_string(), self.href.clone()),
+        ]);
+        attributes
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Geometry {
+    Linear {
+        start: (f32, f32),
+        end: (f32, f32),
+    },
+    Radial {
+        center: (f32, f32),
+        radius: f32,
+        focus: (f32, f32),
+        focal_radius: f32,
+    },
+}
+
+impl Geometry {
+    /// Gradient position of `point`, or `None` outside the painted area.
+    fn position(&self, point: (f32, f32)) -> Option<f32> {
+        match *self {
+            Geometry::Linear { start, end } => {
+                let d = (end.0 - start.0, end.1 - start.1);
+                let length = d.0 * d.0 + d.1 * d.1;
+                if length == 0. {
+                    // The area is painted with the last stop.
+                    return Some(1.);
+                }
+                Some(((point.0 - start.0) * d.0 + (point.1 - start.1) * d.1) / length)
+            }
+            Geometry::Radial {
+                center,
+                radius,
+                focus,
+                focal_radius,
+            } => {
+                if radius == 0. {
+                    return Some(1.);
+                }
+                // Largest t whose circle, interpolated from the focal circle
+                // at 0 to the end circle at 1, passes through the point.
+                let cd = (center.0 - focus.0, center.1 - focus.1);
+                let pd = (point.0 - focus.0, point.1 - focus.1);
+                let dr = radius - focal_radius;
+                let a = cd.0 * cd.0 + cd.1 * cd.1 - dr * dr;
+                let b = pd.0 * cd.0 + pd.1 * cd.1 + focal_radius * dr;
+                let c = pd.0 * pd.0 + pd.1 * pd.1 - focal_radius * focal_radius;
+                let valid = |t: f32| focal_radius + t * dr >= 0.;
+                if a.abs() < 1e-6 {
+                    let t = c / (2. * b);
+                    return (b != 0. && valid(t)).then_some(t);
+                }
+                let discriminant = b * b - a * c;
+                if discriminant < 0. {
+                    return None;
+                }
+                let root = discriminant.sqrt();
+                let (t1, t2) = ((b + root) / a, (b - root) / a);
+                [t1.max(t2), t1.min(t2)].into_iter().find(|&t| valid(t))
+            }
+        }
+    }
+}
+
+/// A gradient with its `href` chain, units and transform resolved, ready to
+/// be evaluated at points in user space.
+#[derive(Clone, Debug)]
+pub struct ResolvedGradient {
+    geometry: Geometry,
+    /// Offset, color and opacity of each stop.
+    stops: Vec<(f32, Color, f32)>,
+    spread_method: SpreadMethod,
+    inverse: Option<Transform>,
+}
+
+impl ResolvedGradient {
+    /// Color and opacity at `point`, in user space.
+    pub fn sample_with_opacity(&self, point: (f32, f32)) -> (Color, f32) {
+        let transparent = (Color::BLACK, 0.);
+        let (Some(inverse), Some(&last)) = (self.inverse, self.stops.last()) else {
+            return transparent;
+        };
+        let Some(t) = self.geometry.position(inverse.apply(point)) else {
+            return transparent;
+        };
+        let t = match self.spread_method {
+            SpreadMethod::Pad => t.clamp(0., 1.),
+            SpreadMethod::Repeat => t - t.floor(),
+            SpreadMethod::Reflect => {
+                let t = t.rem_euclid(2.);
+                match t > 1. {
+                    true => 2. - t,
+                    false => t,
+                }
+            }
+        };
+        let Some(after) = self.stops.iter().position(|stop| stop.0 > t) else {
+            return (last.1, last.2);
+        };
+        if after == 0 {
+            return (self.stops[0].1, self.stops[0].2);
+        }
+        let (from, to) = (self.stops[after - 1], self.stops[after]);
+        let f = (t - from.0) / (to.0 - from.0);
+        let mix = |a: f32, b: f32| a + (b - a) * f;
+        (
+            Color::new(
+                mix(from.1.r, to.1.r),
+                mix(from.1.g, to.1.g),
+                mix(from.1.b, to.1.b),
+            ),
+            mix(from.2, to.2),
+        )
+    }
+
+    /// Color at `point`, in user space, ignoring opacity.
+    pub fn sample(&self, point: (f32, f32)) -> Color {
+        self.sample_with_opacity(point).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::value::color::HexColor;
+
+    fn hex(r: u8, g: u8, b: u8) -> CssColor {
+        CssColor::Hex(HexColor::Six(r, g, b))
+    }
+
+    fn black_to_white() -> Gradient {
+        Gradient::linear(LinearVector::default())
+            .with_stop(Stop::new(Offset(0.), hex(0, 0, 0)))
+            .with_stop(Stop::new(Offset(1.), hex(255, 255, 255)).with_opacity(StopOpacity(0.)))
+    }
+
+    const BOX: Bounds = ((10., 0.), (30., 10.));
+    const CURRENT: (Color, f32) = (Color::BLACK, 1.);
+
+    #[test]
+    fn test_linear_spread() {
+        let gradient = black_to_white();
+        assert_eq!(gradient.name(), "linearGradient");
+        assert_eq!(gradient.children().len(), 2);
+        let resolved = gradient
+            .resolve(|_| None, BOX, (100., 100.), CURRENT)
+            .unwrap();
+        assert_eq!(resolved.sample((15., 5.)), Color::new(0.25, 0.25, 0.25));
+        assert_eq!(resolved.sample_with_opacity((20., 0.)).1, 0.5);
+        assert_eq!(resolved.sample((40., 5.)), Color::WHITE);
+
+        let sample = |spread_method| {
+            black_to_white()
+                .with_spread_method(spread_method)
+                .resolve(|_| None, BOX, (100., 100.), CURRENT)
+                .unwrap()
+                .sample((35., 5.))
+        };
+        assert_eq!(sample(SpreadMethod::Pad), Color::WHITE);
+        assert_eq!(sample(SpreadMethod::Repeat), Color::new(0.25, 0.25, 0.25));
+        assert_eq!(sample(SpreadMethod::Reflect), Color::new(0.75, 0.75, 0.75));
+    }
+
+    #[test]
+    fn test_radial_focus() {
+        let gradient = Gradient::radial(RadialCircles {
+            cx: Some(RegionLength::Number(50.)),
+            cy: Some(RegionLength::Number(50.)),
+            r: Some(RegionLength::Number(40.)),
+            fx: Some(RegionLength::Number(30.)),
+            ..RadialCircles::default()
+        })
+        .with_units(GradientUnits::UserSpaceOnUse)
+        .with_stop(Stop::new(Offset(0.), hex(0, 0, 0)))
+        .with_stop(Stop::new(Offset(1.), hex(255, 255, 255)));
+        assert_eq!(gradient.name(), "radialGradient");
+        assert_eq!(gradient.all_attributes()["fx"], "30");
+        assert!(!gradient.all_attributes().contains_key("fy"));
+        let resolved = gradient
+            .resolve(|_| None, BOX, (100., 100.), CURRENT)
+            .unwrap();
+        assert_eq!(resolved.sample((30., 50.)), Color::BLACK);
+        // The focus sits 20 units left of the center, so the point halfway
+        // between it and the left edge of the end circle is at t = 0.5.
+        assert_eq!(resolved.sample((20., 50.)), Color::new(0.5, 0.5, 0.5));
+        assert_eq!(resolved.sample((90., 50.)), Color::WHITE);
+        assert_eq!(resolved.sample((0., 50.)), Color::WHITE);
+    }
+
+    #[test]
+    fn test_href_inheritance() {
+        let template = black_to_white()
+            .with_units(GradientUnits::UserSpaceOnUse)
+            .with_transform(Transform::scale(2., 1.));
+        let middle = Gradient::linear(LinearVector {
+            x2: Some(RegionLength::Number(50.)),
+            ..LinearVector::default()
+        })
+        .with_href("template");
+        let gradient = Gradient::linear(LinearVector::default()).with_href("#middle");
+        let lookup = |id: &str| match id {
+            "template" => Some(&template),
+            "middle" => Some(&middle),
+            _ => None,
+        };
+        let resolved = gradient
+            .resolve(lookup, BOX, (100., 100.), CURRENT)
+            .unwrap();
+        // x2 = 50 from `middle`, doubled by the transform of `template`.
+        assert_eq!(resolved.sample((25., 0.)), Color::new(0.25, 0.25, 0.25));
+
+        let stops = Gradient::linear(LinearVector::default())
+            .with_stop(Stop::new(Offset(0.), CssColor::Keyword("red".to_string())))
+            .with_href("template");
+        let resolved = stops.resolve(lookup, BOX, (100., 100.), CURRENT).unwrap();
+        assert_eq!(resolved.sample((75., 0.)), Color::RED);
+        let current = Gradient::linear(LinearVector::default())
+            .with_stop(Stop::new(
+                Offset(0.),
+                CssColor::Keyword("currentColor".to_string()),
+            ))
+            .with_href("template");
+        let resolved = current
+            .resolve(lookup, BOX, (100., 100.), (Color::BLUE, 1.))
+            .unwrap();
+        assert_eq!(resolved.sample((75., 0.)), Color::BLUE);
+
+        let cycle = Gradient::linear(LinearVector::default()).with_href("#cycle");
+        assert!(cycle
+            .resolve(|_| Some(&cycle), BOX, (100., 100.), CURRENT)
+            .is_err());
+        assert!(gradient
+            .resolve(|_| None, BOX, (100., 100.), CURRENT)
+            .is_err());
+    }
+}
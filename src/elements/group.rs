@@ -0,0 +1,88 @@
+use crate::{Node, SvgElement};
+use std::collections::HashMap;
+
+/// `<g>` element grouping its children.
+#[derive(Default)]
+pub struct Group {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_child(mut self, child: impl Into<Node>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    pub fn push(&mut self, child: impl Into<Node>) {
+        self.children.push(child.into());
+    }
+
+    pub fn children_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.children
+    }
+}
+
+impl SvgElement for Group {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "g"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::circle::Circle;
+    use crate::elements::rect::Rect;
+    use std::thread;
+
+    #[test]
+    fn test_parallel_traversal() {
+        let group = (0..8).fold(Group::new(), |group, i| {
+            group
+                .with_child(Circle::new((i as f32, 0.), 1.))
+                .with_child(Rect::new((0., i as f32), (2., 2.)))
+        });
+        let areas = thread::scope(|scope| {
+            let handles = group
+                .children()
+                .chunks(4)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .filter_map(|c| c.as_element()?.path_shape())
+                            .map(|s| s.signed_area())
+                            .sum::<f32>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        let total = areas.iter().sum::<f32>();
+        assert!((total - 8. * (std::f32::consts::PI + 4.)).abs() < 0.1);
+        assert_eq!(
+            group.children()[1].as_element().unwrap().all_attributes()["y"],
+            "0"
+        );
+    }
+}
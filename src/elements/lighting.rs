@@ -0,0 +1,297 @@
+use crate::elements::attribute::{
+    Attr, DiffuseConstant, SpecularConstant, SpecularExponent, SurfaceScale,
+};
+use crate::elements::filter::FilterPrimitive;
+use crate::filter::lighting::{diffuse_lighting, specular_lighting, LightSource};
+use crate::render::raster::Canvas;
+use crate::{Color, Node, SvgElement};
+use std::collections::HashMap;
+
+/// `feDistantLight`, `fePointLight` or `feSpotLight`, depending on the source.
+pub struct Light {
+    attributes: HashMap<String, String>,
+    source: LightSource,
+}
+
+impl Light {
+    pub fn new(source: LightSource) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            source,
+        }
+    }
+
+    pub fn source(&self) -> &LightSource {
+        &self.source
+    }
+}
+
+impl SvgElement for Light {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        match self.source {
+            LightSource::Distant { .. } => "feDistantLight",
+            LightSource::Point { .. } => "fePointLight",
+            LightSource::Spot { .. } => "feSpotLight",
+        }
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        match self.source {
+            LightSource::Distant { azimuth, elevation } => vec![typed(azimuth), typed(elevation)],
+            LightSource::Point { position } => coordinates(["x", "y", "z"], position),
+            LightSource::Spot {
+                position,
+                points_at,
+                specular_exponent,
+                limiting_cone_angle,
+            } => {
+                let mut attributes = coordinates(["x", "y", "z"], position);
+                attributes.extend(coordinates(
+                    ["pointsAtX", "pointsAtY", "pointsAtZ"],
+                    points_at,
+                ));
+                attributes.push(typed(specular_exponent));
+                attributes.push((
+                    "limitingConeAngle".to_string(),
+                    limiting_cone_angle.map(|angle| angle.value()),
+                ));
+                attributes
+            }
+        }
+    }
+}
+
+fn typed(attr: impl Attr) -> (String, Option<String>) {
+    (attr.name(), Some(attr.value()))
+}
+
+/// Position attributes of a light, which have no attribute types of their own.
+fn coordinates(names: [&str; 3], point: (f64, f64, f64)) -> Vec<(String, Option<String>)> {
+    let values = [point.0, point.1, point.2];
+    names
+        .into_iter()
+        .zip(values)
+        .map(|(name, value)| (name.to_string(), Some(value.to_string())))
+        .collect()
+}
+
+/// `<feDiffuseLighting>` primitive lit by a single light source child.
+pub struct DiffuseLighting {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+    light: LightSource,
+    surface_scale: SurfaceScale,
+    diffuse_constant: DiffuseConstant,
+    lighting_color: Color,
+}
+
+impl DiffuseLighting {
+    pub fn new(source: LightSource) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            children: vec![Light::new(source).into()],
+            light: source,
+            surface_scale: SurfaceScale::default(),
+            diffuse_constant: DiffuseConstant::default(),
+            lighting_color: Color::WHITE,
+        }
+    }
+
+    pub fn with_surface_scale(mut self, surface_scale: SurfaceScale) -> Self {
+        self.surface_scale = surface_scale;
+        self
+    }
+
+    pub fn with_diffuse_constant(mut self, diffuse_constant: DiffuseConstant) -> Self {
+        self.diffuse_constant = diffuse_constant;
+        self
+    }
+
+    pub fn with_lighting_color(mut self, color: Color) -> Self {
+        self.lighting_color = color;
+        self
+    }
+
+    pub fn light(&self) -> &LightSource {
+        &self.light
+    }
+
+    /// Lights the alpha channel of `input` used as a bump map.
+    pub fn apply(&self, input: &Canvas) -> Canvas {
+        diffuse_lighting(
+            input,
+            &self.light,
+            self.surface_scale,
+            self.diffuse_constant,
+            self.lighting_color,
+        )
+    }
+}
+
+impl SvgElement for DiffuseLighting {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "feDiffuseLighting"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![
+            typed(self.surface_scale),
+            typed(self.diffuse_constant),
+            (
+                "lighting-color".to_string(),
+                Some(self.lighting_color.to_hex_code()),
+            ),
+        ]
+    }
+}
+
+impl FilterPrimitive for DiffuseLighting {}
+
+/// `<feSpecularLighting>` primitive lit by a single light source child.
+pub struct SpecularLighting {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+    light: LightSource,
+    surface_scale: SurfaceScale,
+    specular_constant: SpecularConstant,
+    specular_exponent: SpecularExponent,
+    lighting_color: Color,
+}
+
+impl SpecularLighting {
+    pub fn new(source: LightSource) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            children: vec![Light::new(source).into()],
+            light: source,
+            surface_scale: SurfaceScale::default(),
+            specular_constant: SpecularConstant::default(),
+            specular_exponent: SpecularExponent::default(),
+            lighting_color: Color::WHITE,
+        }
+    }
+
+    pub fn with_surface_scale(mut self, surface_scale: SurfaceScale) -> Self {
+        self.surface_scale = surface_scale;
+        self
+    }
+
+    pub fn with_specular_constant(mut self, specular_constant: SpecularConstant) -> Self {
+        self.specular_constant = specular_constant;
+        self
+    }
+
+    /// The spec limits the exponent to the range 1 to 128.
+    pub fn with_specular_exponent(mut self, specular_exponent: SpecularExponent) -> Self {
+        let specular_exponent = SpecularExponent(specular_exponent.0.clamp(1., 128.));
+        self.specular_exponent = specular_exponent;
+        self
+    }
+
+    pub fn with_lighting_color(mut self, color: Color) -> Self {
+        self.lighting_color = color;
+        self
+    }
+
+    pub fn light(&self) -> &LightSource {
+        &self.light
+    }
+
+    /// Highlights on the alpha channel of `input` used as a bump map.
+    pub fn apply(&self, input: &Canvas) -> Canvas {
+        specular_lighting(
+            input,
+            &self.light,
+            self.surface_scale,
+            self.specular_constant,
+            self.specular_exponent,
+            self.lighting_color,
+        )
+    }
+}
+
+impl SvgElement for SpecularLighting {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "feSpecularLighting"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![
+            typed(self.surface_scale),
+            typed(self.specular_constant),
+            typed(self.specular_exponent),
+            (
+                "lighting-color".to_string(),
+                Some(self.lighting_color.to_hex_code()),
+            ),
+        ]
+    }
+}
+
+impl FilterPrimitive for SpecularLighting {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::attribute::{LimitingConeAngle, SpecularExponent};
+
+    #[test]
+    fn test_light_children() {
+        let spot = LightSource::Spot {
+            position: (1., 2., 3.),
+            points_at: (0., 0., 0.),
+            specular_exponent: SpecularExponent(2.),
+            limiting_cone_angle: Some(LimitingConeAngle(30.)),
+        };
+        let lighting = SpecularLighting::new(spot)
+            .with_specular_exponent(SpecularExponent(500.))
+            .with_lighting_color(Color::RED);
+        assert_eq!(lighting.all_attributes()["specularExponent"], "128");
+        assert_eq!(lighting.all_attributes()["lighting-color"], "#FF0000");
+        let light = lighting.children()[0].as_element().unwrap();
+        assert_eq!(light.name(), "feSpotLight");
+        assert_eq!(light.all_attributes()["pointsAtX"], "0");
+        assert_eq!(light.all_attributes()["limitingConeAngle"], "30");
+
+        let diffuse = DiffuseLighting::new(LightSource::Point {
+            position: (0., 0., 5.),
+        });
+        assert_eq!(
+            diffuse.children()[0].as_element().unwrap().name(),
+            "fePointLight"
+        );
+        assert_eq!(diffuse.apply(&Canvas::new(2, 2)).data[0][3], 1.);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::elements::path::{shapes, PathShape};
+use crate::SvgElement;
+use std::collections::HashMap;
+
+pub struct Line {
+    from: (f32, f32), // x1, y1
+    to: (f32, f32),   // x2, y2
+    attributes: HashMap<String, String>,
+    value: Option<String>,
+}
+
+impl Line {
+    pub fn new(from: (f32, f32), to: (f32, f32)) -> Self {
+        Self {
+            from,
+            to,
+            attributes: HashMap::new(),
+            value: None,
+        }
+    }
+
+    pub fn from_pos(&self) -> (f32, f32) {
+        self.from
+    }
+
+    pub fn to_pos(&self) -> (f32, f32) {
+        self.to
+    }
+
+    pub fn to_path_shape(&self) -> PathShape {
+        shapes::polyline(&[self.from, self.to], false)
+    }
+}
+
+impl SvgElement for Line {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        "line"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![
+            ("x1".to_string(), Some(self.from.0.to_string())),
+            ("y1".to_string(), Some(self.from.1.to_string())),
+            ("x2".to_string(), Some(self.to.0.to_string())),
+            ("y2".to_string(), Some(self.to.1.to_string())),
+        ]
+    }
+
+    fn value(&self) -> Option<&String> {
+        self.value.as_ref()
+    }
+
+    fn path_shape(&self) -> Option<PathShape> {
+        Some(self.to_path_shape())
+    }
+}
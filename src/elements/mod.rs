@@ -1,4 +1,23 @@
+pub mod animate;
 pub mod attribute;
+pub mod circle;
+pub mod component_transfer;
+pub mod displacement;
 pub mod ellipse;
+pub mod filter;
+pub mod generic;
+pub mod gradient;
+pub mod group;
+pub mod lighting;
+pub mod line;
 pub mod path;
-mod value;
+pub mod pick;
+pub mod polygon;
+pub mod polyline;
+pub mod rect;
+pub mod script;
+pub mod style;
+pub mod text;
+pub mod title;
+pub mod turbulence;
+pub mod value;
@@ -1,8 +1,21 @@
+pub mod boolean;
+pub mod fit;
+pub mod format;
+pub mod hit;
+pub mod intersect;
+pub mod measure;
+pub mod morph;
+pub mod offset;
+pub mod segment;
+pub mod shapes;
+pub mod transform;
+
+use crate::elements::path::format::PathFormat;
 use crate::{Attribute, SvgElement, UkkoError, UkkoResult};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum PathCommandKind {
     MoveTo,
     LineTo,
@@ -33,7 +46,7 @@ impl PathCommandKind {
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct PathCommand {
     pub relative: bool,
     pub movement: (f32, f32),
@@ -142,7 +155,7 @@ impl PathCommand {
         if splits.len() != 2 {
             return Err(UkkoError::parse("Not a tuple."));
         }
-        Ok((splits[1].parse::<f32>()?, splits[1].parse::<f32>()?))
+        Ok((splits[0].parse::<f32>()?, splits[1].parse::<f32>()?))
     }
 
     pub fn parse_from_str(str: &str) -> Result<Self, UkkoError> {
@@ -322,7 +335,7 @@ impl Display for PathCommand {
     }
 }
 
-#[derive(PartialEq, Debug, Default)]
+#[derive(PartialEq, Debug, Default, Clone)]
 pub struct PathShape {
     pub elements: Vec<PathCommand>,
 }
@@ -335,7 +348,8 @@ impl PathShape {
     pub fn to_path(self) -> Path {
         Path {
             shape: self,
-            attributes: Default::default(),
+            format: None,
+            attributes: HashMap::new(),
             value: None,
         }
     }
@@ -392,34 +406,50 @@ impl Display for PathShape {
     }
 }
 
+#[derive(Clone)]
 pub struct Path {
     shape: PathShape,
+    format: Option<PathFormat>,
     attributes: HashMap<String, String>,
     value: Option<String>,
 }
 
-impl SvgElement for Path {
-    fn children(&self) -> Vec<Box<dyn SvgElement>> {
-        vec![]
+impl Path {
+    /// Writes the `d` attribute with `format` instead of the `Display` output.
+    pub fn with_format(mut self, format: PathFormat) -> Self {
+        self.format = Some(format);
+        self
     }
+}
 
-    fn attributes(&self) -> HashMap<String, String> {
-        let mut map = self.attributes.clone();
-        map.insert("d".to_string(), self.shape.to_string());
-        map
+impl SvgElement for Path {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
     }
 
     fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
         &mut self.attributes
     }
 
-    fn name(&self) -> String {
-        "path".to_string()
+    fn name(&self) -> &str {
+        "path"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let d = match &self.format {
+            Some(format) => self.shape.format(format),
+            None => self.shape.to_string(),
+        };
+        vec![("d".to_string(), Some(d))]
     }
 
     fn value(&self) -> Option<&String> {
         self.value.as_ref()
     }
+
+    fn path_shape(&self) -> Option<PathShape> {
+        Some(self.shape.clone())
+    }
 }
 
 #[cfg(test)]
@@ -441,6 +471,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_to_xml_formatted() {
+        let mut shape = PathShape::parse_from_str("M 10,10 L 20.5,10 Z")
+            .unwrap()
+            .to_path()
+            .with_format(PathFormat::minified(2));
+        // The typed shape wins over a `d` set by hand, as it is what gets drawn.
+        shape.with_attribute("d".to_string(), "M 0,0".to_string());
+        assert_eq!(shape.all_attributes()["d"], "M10 10H20.5Z");
+    }
+
     #[test]
     fn test_shape_parse() {
         let c_str = "M 10,10";
@@ -0,0 +1,339 @@
+use crate::elements::path::hit::{fill_rule_matches, polyline_winding};
+use crate::elements::path::segment::DEFAULT_TOLERANCE;
+use crate::elements::path::{PathCommand, PathShape};
+use crate::elements::value::FillRule;
+use std::collections::HashMap;
+
+/// Set operation combining the filled areas of two paths.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    Difference,
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(&self, a: bool, b: bool) -> bool {
+        match self {
+            BooleanOp::Union => a || b,
+            BooleanOp::Intersection => a && b,
+            BooleanOp::Difference => a && !b,
+            BooleanOp::Xor => a != b,
+        }
+    }
+}
+
+pub(crate) type Point = (f64, f64);
+
+pub(crate) fn flatten(shape: &PathShape, tolerance: f32) -> Vec<Vec<Point>> {
+    shape
+        .flatten(tolerance)
+        .into_iter()
+        .map(|p| {
+            p.into_iter()
+                .map(|(x, y)| (x as f64, y as f64))
+                .collect::<Vec<_>>()
+        })
+        .filter(|p| p.len() > 2)
+        .collect()
+}
+
+pub(crate) fn inside(polygons: &[Vec<Point>], fill_rule: FillRule, point: Point) -> bool {
+    fill_rule_matches(fill_rule, polyline_winding(polygons, point))
+}
+
+/// Points where `a`-`b` and `c`-`d` touch, with the parameter of each point on
+/// the first and second segment if it lies strictly inside that segment.
+fn intersect(a: Point, b: Point, c: Point, d: Point) -> Vec<(Option<f64>, Option<f64>, Point)> {
+    let r = (b.0 - a.0, b.1 - a.1);
+    let s = (d.0 - c.0, d.1 - c.1);
+    let qp = (c.0 - a.0, c.1 - a.1);
+    let rr = r.0 * r.0 + r.1 * r.1;
+    let ss = s.0 * s.0 + s.1 * s.1;
+    let denom = r.0 * s.1 - r.1 * s.0;
+    let interior = |v: f64| (v > 0. && v < 1.).then_some(v);
+    if denom.abs() <= 1e-12 * (rr * ss).sqrt() {
+        // Parallel: overlapping collinear segments split at each other's endpoints.
+        if (qp.0 * r.1 - qp.1 * r.0).abs() > 1e-9 * rr.max(1.) {
+            return vec![];
+        }
+        let on_first = |p: Point| ((p.0 - a.0) * r.0 + (p.1 - a.1) * r.1) / rr;
+        let on_second = |p: Point| ((p.0 - c.0) * s.0 + (p.1 - c.1) * s.1) / ss;
+        let mut result = vec![];
+        for p in [c, d] {
+            if let Some(t) = interior(on_first(p)) {
+                result.push((Some(t), None, p));
+            }
+        }
+        for p in [a, b] {
+            if let Some(u) = interior(on_second(p)) {
+                result.push((None, Some(u), p));
+            }
+        }
+        return result;
+    }
+    let t = (qp.0 * s.1 - qp.1 * s.0) / denom;
+    let u = (qp.0 * r.1 - qp.1 * r.0) / denom;
+    if !(0. ..=1.).contains(&t) || !(0. ..=1.).contains(&u) {
+        return vec![];
+    }
+    let point = match (t, u) {
+        (0., _) => a,
+        (1., _) => b,
+        (_, 0.) => c,
+        (_, 1.) => d,
+        _ => (a.0 + r.0 * t, a.1 + r.1 * t),
+    };
+    vec![(interior(t), interior(u), point)]
+}
+
+/// Splits all edges at their mutual intersections and returns the resulting
+/// edges as pairs of points.
+fn split_edges(polygons: &[Vec<Point>]) -> Vec<(Point, Point)> {
+    let edges = polygons
+        .iter()
+        .flat_map(|p| (0..p.len()).map(move |i| (p[i], p[(i + 1) % p.len()])))
+        .filter(|(a, b)| a != b)
+        .collect::<Vec<_>>();
+    let bounds = edges
+        .iter()
+        .map(|(a, b)| ((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1))))
+        .collect::<Vec<_>>();
+    let mut splits: Vec<Vec<(f64, Point)>> = vec![vec![]; edges.len()];
+    for i in 0..edges.len() {
+        for j in i + 1..edges.len() {
+            let (bi, bj) = (bounds[i], bounds[j]);
+            if bi.1 .0 < bj.0 .0 || bj.1 .0 < bi.0 .0 || bi.1 .1 < bj.0 .1 || bj.1 .1 < bi.0 .1 {
+                continue;
+            }
+            let (a, b) = edges[i];
+            let (c, d) = edges[j];
+            for (t, u, point) in intersect(a, b, c, d) {
+                if let Some(t) = t {
+                    splits[i].push((t, point));
+                }
+                if let Some(u) = u {
+                    splits[j].push((u, point));
+                }
+            }
+        }
+    }
+
+    let mut result = vec![];
+    for (edge, mut split) in edges.into_iter().zip(splits) {
+        split.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let mut prev = edge.0;
+        for (_, p) in split.into_iter().chain([(1., edge.1)]) {
+            if p != prev {
+                result.push((prev, p));
+                prev = p;
+            }
+        }
+    }
+    result
+}
+
+fn quantize(p: Point, grid: f64) -> (i64, i64) {
+    ((p.0 / grid).round() as i64, (p.1 / grid).round() as i64)
+}
+
+/// Computes the outline of the region selected by `select` and returns it as
+/// polygons with the filled area on the left of every edge. A walk that
+/// dead-ends, which only rounding can cause, is kept as a ring closed back to
+/// its start rather than dropped with its area.
+pub(crate) fn boolean_polygons(
+    polygons: &[Vec<Point>],
+    select: impl Fn(Point) -> bool,
+    scale: f64,
+) -> Vec<Vec<Point>> {
+    let grid = scale * 1e-9;
+    let mut seen = HashMap::new();
+    let mut boundary: Vec<(Point, Point)> = vec![];
+    for (a, b) in split_edges(polygons) {
+        let (qa, qb) = (quantize(a, grid), quantize(b, grid));
+        if qa == qb || seen.insert((qa.min(qb), qa.max(qb)), ()).is_some() {
+            continue;
+        }
+        let d = (b.0 - a.0, b.1 - a.1);
+        let len = (d.0 * d.0 + d.1 * d.1).sqrt();
+        let eps = (scale * 1e-6).min(len * 0.25);
+        let normal = (-d.1 / len * eps, d.0 / len * eps);
+        let mid = ((a.0 + b.0) / 2., (a.1 + b.1) / 2.);
+        let left = select((mid.0 + normal.0, mid.1 + normal.1));
+        let right = select((mid.0 - normal.0, mid.1 - normal.1));
+        match (left, right) {
+            (true, false) => boundary.push((a, b)),
+            (false, true) => boundary.push((b, a)),
+            _ => {}
+        }
+    }
+
+    let mut outgoing: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (i, (a, _)) in boundary.iter().enumerate() {
+        outgoing.entry(quantize(*a, grid)).or_default().push(i);
+    }
+    let mut used = vec![false; boundary.len()];
+    let mut result = vec![];
+    for first in 0..boundary.len() {
+        if used[first] {
+            continue;
+        }
+        let start = quantize(boundary[first].0, grid);
+        let mut ring = vec![boundary[first].0];
+        let mut current = first;
+        used[first] = true;
+        loop {
+            let end = boundary[current].1;
+            let key = quantize(end, grid);
+            if key == start {
+                break;
+            }
+            ring.push(end);
+            let Some(&next) = outgoing
+                .get(&key)
+                .and_then(|e| e.iter().find(|&&e| !used[e]))
+            else {
+                break;
+            };
+            used[next] = true;
+            current = next;
+        }
+        remove_collinear(&mut ring);
+        if ring.len() > 2 {
+            result.push(ring);
+        }
+    }
+    result
+}
+
+fn remove_collinear(ring: &mut Vec<Point>) {
+    let mut i = 0;
+    while ring.len() > 2 && i < ring.len() {
+        let prev = ring[(i + ring.len() - 1) % ring.len()];
+        let next = ring[(i + 1) % ring.len()];
+        let p = ring[i];
+        let cross = (p.0 - prev.0) * (next.1 - prev.1) - (p.1 - prev.1) * (next.0 - prev.0);
+        let scale = ((next.0 - prev.0).abs() + (next.1 - prev.1).abs()).max(1e-12);
+        if cross.abs() <= 1e-9 * scale * scale {
+            ring.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+pub(crate) fn to_shape(polygons: Vec<Vec<Point>>) -> PathShape {
+    let mut elements = vec![];
+    for polygon in polygons {
+        let mut points = polygon.into_iter().map(|(x, y)| (x as f32, y as f32));
+        elements.push(PathCommand::move_to(points.next().unwrap()));
+        elements.extend(points.map(PathCommand::line_to));
+        elements.push(PathCommand::close());
+    }
+    PathShape { elements }
+}
+
+pub(crate) fn scale_of(polygons: &[Vec<Point>]) -> f64 {
+    polygons
+        .iter()
+        .flatten()
+        .fold(1f64, |s, p| s.max(p.0.abs()).max(p.1.abs()))
+}
+
+impl PathShape {
+    /// Combines the filled areas of `self` and `other` with the given operation.
+    ///
+    /// Curves are flattened within `tolerance`, so the result consists of
+    /// straight lines only. Every resulting subpath has its filled area on the
+    /// same side, so the result renders identically with either fill rule.
+    pub fn boolean(
+        &self,
+        fill_rule: FillRule,
+        other: &PathShape,
+        other_fill_rule: FillRule,
+        op: BooleanOp,
+        tolerance: f32,
+    ) -> PathShape {
+        let a = flatten(self, tolerance);
+        let b = flatten(other, tolerance);
+        let all = a.iter().chain(b.iter()).cloned().collect::<Vec<_>>();
+        let scale = scale_of(&all);
+        to_shape(boolean_polygons(
+            &all,
+            |p| op.apply(inside(&a, fill_rule, p), inside(&b, other_fill_rule, p)),
+            scale,
+        ))
+    }
+
+    pub fn union(&self, other: &PathShape) -> PathShape {
+        self.boolean_nonzero(other, BooleanOp::Union)
+    }
+
+    pub fn intersection(&self, other: &PathShape) -> PathShape {
+        self.boolean_nonzero(other, BooleanOp::Intersection)
+    }
+
+    pub fn difference(&self, other: &PathShape) -> PathShape {
+        self.boolean_nonzero(other, BooleanOp::Difference)
+    }
+
+    pub fn xor(&self, other: &PathShape) -> PathShape {
+        self.boolean_nonzero(other, BooleanOp::Xor)
+    }
+
+    fn boolean_nonzero(&self, other: &PathShape, op: BooleanOp) -> PathShape {
+        self.boolean(
+            FillRule::NonZero,
+            other,
+            FillRule::NonZero,
+            op,
+            DEFAULT_TOLERANCE,
+        )
+    }
+
+    /// Merges overlapping and self-intersecting subpaths into the outline of
+    /// the area filled under `fill_rule`.
+    pub fn resolve(&self, fill_rule: FillRule, tolerance: f32) -> PathShape {
+        let a = flatten(self, tolerance);
+        let scale = scale_of(&a);
+        to_shape(boolean_polygons(&a, |p| inside(&a, fill_rule, p), scale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boolean_squares() {
+        let a = PathShape::parse_from_str("M 0,0 H 10 V 10 H 0 Z").unwrap();
+        let b = PathShape::parse_from_str("M 5,5 H 15 V 15 H 5 Z").unwrap();
+        let inside = |s: &PathShape, p| s.contains(p, FillRule::EvenOdd);
+
+        let union = a.union(&b);
+        assert_eq!(union.subpaths().len(), 1);
+        assert_eq!(union.elements.len(), 9);
+        assert!(inside(&union, (2., 2.)) && inside(&union, (12., 12.)));
+        assert!(!inside(&union, (12., 2.)));
+
+        let intersection = a.intersection(&b);
+        assert!(inside(&intersection, (7., 7.)) && !inside(&intersection, (2., 2.)));
+
+        let difference = a.difference(&b);
+        assert!(inside(&difference, (2., 2.)) && !inside(&difference, (7., 7.)));
+
+        let xor = a.xor(&b);
+        assert!(inside(&xor, (2., 2.)) && inside(&xor, (12., 12.)));
+        assert!(!inside(&xor, (7., 7.)));
+    }
+
+    #[test]
+    fn test_resolve_shared_edge() {
+        let shape =
+            PathShape::parse_from_str("M 0,0 H 10 V 10 H 0 Z M 10,0 H 20 V 10 H 10 Z").unwrap();
+        let resolved = shape.resolve(FillRule::NonZero, 0.05);
+        assert_eq!(resolved.subpaths().len(), 1);
+        assert_eq!(resolved.elements.len(), 5);
+    }
+}
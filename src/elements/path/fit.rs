@@ -0,0 +1,323 @@
+use crate::elements::path::hit::segment_distance;
+use crate::elements::path::segment::{add, dot, scale, sub};
+use crate::elements::path::{PathCommand, PathShape};
+
+type Point = (f64, f64);
+
+fn distance(a: Point, b: Point) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+fn normalize(a: Point) -> Point {
+    let len = a.0.hypot(a.1);
+    if len == 0. {
+        a
+    } else {
+        scale(a, 1. / len)
+    }
+}
+
+fn to_f64(points: &[(f32, f32)]) -> Vec<Point> {
+    let mut result = points
+        .iter()
+        .map(|&(x, y)| (x as f64, y as f64))
+        .collect::<Vec<_>>();
+    result.dedup();
+    result
+}
+
+fn to_f32(p: Point) -> (f32, f32) {
+    (p.0 as f32, p.1 as f32)
+}
+
+/// Removes points that deviate less than `tolerance` from the simplified line
+/// (Ramer–Douglas–Peucker). The first and last point are always kept.
+pub fn simplify_polyline(points: &[(f32, f32)], tolerance: f32) -> Vec<(f32, f32)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let (a, b) = (points[first], points[last]);
+        let farthest = (first + 1..last)
+            .map(|i| (i, segment_distance(a, b, points[i])))
+            .max_by(|x, y| x.1.total_cmp(&y.1));
+        if let Some((index, dist)) = farthest {
+            if dist > tolerance {
+                keep[index] = true;
+                stack.push((first, index));
+                stack.push((index, last));
+            }
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter(|(_, k)| *k)
+        .map(|(p, _)| *p)
+        .collect()
+}
+
+type Cubic = [Point; 4];
+
+fn bezier(c: &Cubic, t: f64) -> Point {
+    let mt = 1. - t;
+    let (b0, b1, b2, b3) = (mt * mt * mt, 3. * mt * mt * t, 3. * mt * t * t, t * t * t);
+    add(
+        add(scale(c[0], b0), scale(c[1], b1)),
+        add(scale(c[2], b2), scale(c[3], b3)),
+    )
+}
+
+fn bezier_derivative(c: &Cubic, t: f64) -> Point {
+    let mt = 1. - t;
+    add(
+        add(
+            scale(sub(c[1], c[0]), 3. * mt * mt),
+            scale(sub(c[2], c[1]), 6. * mt * t),
+        ),
+        scale(sub(c[3], c[2]), 3. * t * t),
+    )
+}
+
+fn bezier_second_derivative(c: &Cubic, t: f64) -> Point {
+    let a = sub(add(c[2], c[0]), scale(c[1], 2.));
+    let b = sub(add(c[3], c[1]), scale(c[2], 2.));
+    add(scale(a, 6. * (1. - t)), scale(b, 6. * t))
+}
+
+/// Least-squares fit of a cubic with fixed end tangents to `points` at the
+/// parameters `u`.
+fn generate_bezier(points: &[Point], u: &[f64], tangent1: Point, tangent2: Point) -> Cubic {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (mut c00, mut c01, mut c11, mut x0, mut x1) = (0., 0., 0., 0., 0.);
+    for (&p, &t) in points.iter().zip(u) {
+        let mt = 1. - t;
+        let a0 = scale(tangent1, 3. * mt * mt * t);
+        let a1 = scale(tangent2, 3. * mt * t * t);
+        c00 += dot(a0, a0);
+        c01 += dot(a0, a1);
+        c11 += dot(a1, a1);
+        let fixed = bezier(&[first, first, last, last], t);
+        let diff = sub(p, fixed);
+        x0 += dot(a0, diff);
+        x1 += dot(a1, diff);
+    }
+    let det = c00 * c11 - c01 * c01;
+    let (alpha1, alpha2) = if det.abs() > 1e-12 {
+        ((x0 * c11 - x1 * c01) / det, (c00 * x1 - c01 * x0) / det)
+    } else {
+        (0., 0.)
+    };
+    let seg_length = distance(first, last);
+    let epsilon = 1e-6 * seg_length;
+    if alpha1 < epsilon || alpha2 < epsilon {
+        // Fall back to Wu/Barsky's heuristic.
+        let d = seg_length / 3.;
+        return [
+            first,
+            add(first, scale(tangent1, d)),
+            add(last, scale(tangent2, d)),
+            last,
+        ];
+    }
+    [
+        first,
+        add(first, scale(tangent1, alpha1)),
+        add(last, scale(tangent2, alpha2)),
+        last,
+    ]
+}
+
+fn chord_parameters(points: &[Point]) -> Vec<f64> {
+    let mut u = vec![0.];
+    for i in 1..points.len() {
+        u.push(u[i - 1] + distance(points[i], points[i - 1]));
+    }
+    let total = u[u.len() - 1];
+    u.iter_mut().for_each(|t| *t /= total);
+    u
+}
+
+/// One Newton-Raphson step towards the parameter closest to each point.
+fn reparameterize(cubic: &Cubic, points: &[Point], u: &[f64]) -> Vec<f64> {
+    points
+        .iter()
+        .zip(u)
+        .map(|(&p, &t)| {
+            let d = sub(bezier(cubic, t), p);
+            let d1 = bezier_derivative(cubic, t);
+            let d2 = bezier_second_derivative(cubic, t);
+            let denominator = dot(d1, d1) + dot(d, d2);
+            if denominator.abs() < 1e-12 {
+                t
+            } else {
+                (t - dot(d, d1) / denominator).clamp(0., 1.)
+            }
+        })
+        .collect()
+}
+
+/// Largest squared distance of an inner point from the curve and its index.
+fn max_error(cubic: &Cubic, points: &[Point], u: &[f64]) -> (f64, usize) {
+    let mut result = (0., points.len() / 2);
+    for i in 1..points.len() - 1 {
+        let d = sub(bezier(cubic, u[i]), points[i]);
+        let error = dot(d, d);
+        if error >= result.0 {
+            result = (error, i);
+        }
+    }
+    result
+}
+
+fn fit_cubic(points: &[Point], tangent1: Point, tangent2: Point, error: f64, out: &mut Vec<Cubic>) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    if points.len() == 2 {
+        let d = distance(first, last) / 3.;
+        out.push([
+            first,
+            add(first, scale(tangent1, d)),
+            add(last, scale(tangent2, d)),
+            last,
+        ]);
+        return;
+    }
+    let mut u = chord_parameters(points);
+    let mut cubic = generate_bezier(points, &u, tangent1, tangent2);
+    let (mut worst, mut split) = max_error(&cubic, points, &u);
+    if worst < error {
+        out.push(cubic);
+        return;
+    }
+    if worst < error * 4. {
+        for _ in 0..4 {
+            u = reparameterize(&cubic, points, &u);
+            cubic = generate_bezier(points, &u, tangent1, tangent2);
+            (worst, split) = max_error(&cubic, points, &u);
+            if worst < error {
+                out.push(cubic);
+                return;
+            }
+        }
+    }
+    let center = normalize(sub(points[split - 1], points[split + 1]));
+    fit_cubic(&points[..=split], tangent1, center, error, out);
+    fit_cubic(&points[split..], scale(center, -1.), tangent2, error, out);
+}
+
+impl PathShape {
+    /// Fits a sequence of cubic Bézier curves through dense input points
+    /// (Schneider's algorithm), keeping every point within `tolerance`.
+    pub fn fit_curve(points: &[(f32, f32)], tolerance: f32) -> PathShape {
+        let points = to_f64(points);
+        let Some(&first) = points.first() else {
+            return PathShape::new();
+        };
+        let mut commands = vec![PathCommand::move_to(to_f32(first))];
+        if points.len() > 1 {
+            let n = points.len();
+            let tangent1 = normalize(sub(points[1], points[0]));
+            let tangent2 = normalize(sub(points[n - 2], points[n - 1]));
+            let tolerance = tolerance as f64;
+            let mut cubics = vec![];
+            fit_cubic(
+                &points,
+                tangent1,
+                tangent2,
+                tolerance * tolerance,
+                &mut cubics,
+            );
+            commands.extend(cubics.iter().map(|c| {
+                PathCommand::cubic_bezier_curve(to_f32(c[3]), to_f32(c[1]), to_f32(c[2]))
+            }));
+        }
+        PathShape::new().with_commands(commands)
+    }
+
+    /// Smooth curve passing through every point, built from a uniform
+    /// Catmull-Rom spline.
+    pub fn catmull_rom(points: &[(f32, f32)], closed: bool) -> PathShape {
+        let points = to_f64(points);
+        let Some(&first) = points.first() else {
+            return PathShape::new();
+        };
+        let n = points.len();
+        let at = |i: isize| {
+            if closed {
+                points[i.rem_euclid(n as isize) as usize]
+            } else {
+                points[i.clamp(0, n as isize - 1) as usize]
+            }
+        };
+        let mut commands = vec![PathCommand::move_to(to_f32(first))];
+        let count = if closed { n } else { n - 1 };
+        for i in 0..count as isize {
+            let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+            let c1 = add(p1, scale(sub(p2, p0), 1. / 6.));
+            let c2 = sub(p2, scale(sub(p3, p1), 1. / 6.));
+            commands.push(PathCommand::cubic_bezier_curve(
+                to_f32(p2),
+                to_f32(c1),
+                to_f32(c2),
+            ));
+        }
+        if closed && n > 1 {
+            commands.push(PathCommand::close());
+        }
+        PathShape::new().with_commands(commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::path::segment::Segment;
+
+    #[test]
+    fn test_simplify_polyline() {
+        let points = [(0., 0.), (1., 0.01), (2., -0.01), (3., 0.), (3., 3.)];
+        assert_eq!(
+            simplify_polyline(&points, 0.1),
+            vec![(0., 0.), (3., 0.), (3., 3.)]
+        );
+    }
+
+    #[test]
+    fn test_fit_curve() {
+        let points = (0..=50)
+            .map(|i| {
+                let a = std::f32::consts::PI * i as f32 / 50.;
+                (10. * a.cos(), 10. * a.sin())
+            })
+            .collect::<Vec<_>>();
+        let shape = PathShape::fit_curve(&points, 0.05);
+        assert!(shape.elements.len() <= 5);
+        let segments = &shape.subpaths()[0].segments;
+        for s in segments {
+            for i in 0..=10 {
+                let (x, y) = s.eval(i as f32 / 10.);
+                assert!((x.hypot(y) - 10.).abs() < 0.1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_catmull_rom() {
+        let points = [(0., 0.), (10., 10.), (20., 0.)];
+        let shape = PathShape::catmull_rom(&points, false);
+        let segments = &shape.subpaths()[0].segments;
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].end(), (10., 10.));
+        let Segment::Cubic(_, _, c2, _) = segments[0] else {
+            panic!("expected a cubic");
+        };
+        let Segment::Cubic(_, c1, _, _) = segments[1] else {
+            panic!("expected a cubic");
+        };
+        assert_eq!((c1.0 + c2.0) / 2., 10.);
+    }
+}
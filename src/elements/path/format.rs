@@ -0,0 +1,355 @@
+use crate::elements::path::{PathCommandKind, PathShape};
+
+/// How numbers within a command are separated.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Separator {
+    /// `M 10,20 L 30,40`: coordinate pairs joined by commas.
+    #[default]
+    Comma,
+    /// `M 10 20 L 30 40`
+    Space,
+    /// `M10 20L30-40`: separators only where numbers would run together.
+    Minimal,
+}
+
+/// Whether commands are written with absolute or relative coordinates.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CoordinateMode {
+    /// Keeps the mode each command was created with.
+    #[default]
+    Preserve,
+    Absolute,
+    Relative,
+    /// Picks whichever form is shorter for each command.
+    Shortest,
+}
+
+/// Options for writing path data.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathFormat {
+    /// Maximum number of decimals, or `None` for full precision.
+    pub precision: Option<usize>,
+    pub separator: Separator,
+    pub coordinates: CoordinateMode,
+    /// Omits a command letter if it repeats the previous one.
+    pub drop_repeated_commands: bool,
+    /// Writes `0.5` as `.5`.
+    pub drop_leading_zeros: bool,
+    /// Writes horizontal and vertical lines as `H` and `V`.
+    pub shorthand_lines: bool,
+}
+
+impl PathFormat {
+    /// Settings producing the shortest output at the given precision.
+    pub fn minified(precision: usize) -> Self {
+        Self {
+            precision: Some(precision),
+            separator: Separator::Minimal,
+            coordinates: CoordinateMode::Shortest,
+            drop_repeated_commands: true,
+            drop_leading_zeros: true,
+            shorthand_lines: true,
+        }
+    }
+
+    pub fn with_precision(mut self, precision: Option<usize>) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn with_separator(mut self, separator: Separator) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn with_coordinates(mut self, coordinates: CoordinateMode) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    fn round(&self, value: f64) -> f64 {
+        match self.precision {
+            Some(p) => {
+                let factor = 10f64.powi(p as i32);
+                (value * factor).round() / factor
+            }
+            None => value as f32 as f64,
+        }
+    }
+
+    fn number(&self, value: f64) -> String {
+        let mut text = match self.precision {
+            Some(p) => {
+                let text = format!("{:.*}", p, value);
+                if text.contains('.') {
+                    text.trim_end_matches('0').trim_end_matches('.').to_string()
+                } else {
+                    text
+                }
+            }
+            None => (value as f32).to_string(),
+        };
+        if text == "-0" {
+            text = "0".to_string();
+        }
+        if self.drop_leading_zeros {
+            if let Some(rest) = text.strip_prefix("0.") {
+                text = format!(".{}", rest);
+            } else if let Some(rest) = text.strip_prefix("-0.") {
+                text = format!("-.{}", rest);
+            }
+        }
+        text
+    }
+
+    /// Joins number tokens, where `pairs` groups coordinates with commas.
+    fn join(&self, tokens: &[String], pairs: &[bool]) -> String {
+        let mut result = String::new();
+        for (i, token) in tokens.iter().enumerate() {
+            if i > 0 {
+                match self.separator {
+                    Separator::Comma if pairs[i] => result.push(','),
+                    Separator::Comma | Separator::Space => result.push(' '),
+                    Separator::Minimal => {
+                        if needs_separator(&result, token) {
+                            result.push(' ');
+                        }
+                    }
+                }
+            }
+            result.push_str(token);
+        }
+        result
+    }
+}
+
+/// Returns `true` if `next` would be read as part of the number ending `text`.
+fn needs_separator(text: &str, next: &str) -> bool {
+    if next.starts_with('-') {
+        return false;
+    }
+    if next.starts_with('.') {
+        let last = text
+            .rsplit(|c: char| c == ' ' || c == '-' || c.is_ascii_alphabetic())
+            .next()
+            .unwrap_or("");
+        return !last.contains('.');
+    }
+    true
+}
+
+/// A command resolved to absolute coordinates.
+struct Resolved {
+    kind: char,
+    relative: bool,
+    controls: Vec<(f64, f64)>,
+    end: (f64, f64),
+    arc: Option<(f32, f32, f32, bool, bool)>,
+}
+
+fn resolve(shape: &PathShape) -> Vec<Resolved> {
+    let mut pos = (0f64, 0f64);
+    let mut start = (0f64, 0f64);
+    let mut result = vec![];
+    for command in &shape.elements {
+        let relative = command.relative;
+        let point = |p: (f32, f32)| {
+            if relative {
+                (pos.0 + p.0 as f64, pos.1 + p.1 as f64)
+            } else {
+                (p.0 as f64, p.1 as f64)
+            }
+        };
+        let (mx, my) = (command.movement.0 as f64, command.movement.1 as f64);
+        let end = match command.command {
+            PathCommandKind::HorizontalLineTo if relative => (pos.0 + mx, pos.1),
+            PathCommandKind::HorizontalLineTo => (mx, pos.1),
+            PathCommandKind::VerticalLineTo if relative => (pos.0, pos.1 + my),
+            PathCommandKind::VerticalLineTo => (pos.0, my),
+            PathCommandKind::ClosePath => start,
+            _ => point(command.movement),
+        };
+        let controls = match command.command {
+            PathCommandKind::CubicBezierCurve(c1, c2) => vec![point(c1), point(c2)],
+            PathCommandKind::CubicBezierCurveSmooth(c)
+            | PathCommandKind::QuadraticBezierCurve(c) => {
+                vec![point(c)]
+            }
+            _ => vec![],
+        };
+        let arc = match command.command {
+            PathCommandKind::EllipticalArcCurve(rx, ry, angle, large, sweep) => {
+                Some((rx, ry, angle, large, sweep))
+            }
+            _ => None,
+        };
+        if command.command == PathCommandKind::MoveTo {
+            start = end;
+        }
+        pos = end;
+        result.push(Resolved {
+            kind: command.command.as_char(),
+            relative,
+            controls,
+            end,
+            arc,
+        });
+    }
+    result
+}
+
+impl PathShape {
+    /// Writes the path data with the given options.
+    pub fn format(&self, options: &PathFormat) -> String {
+        let mut out = String::new();
+        let mut last_letter: Option<char> = None;
+        // Current point as a reader of the output sees it.
+        let mut pos = (0f64, 0f64);
+        for command in resolve(self) {
+            let end = (options.round(command.end.0), options.round(command.end.1));
+            let controls = command
+                .controls
+                .iter()
+                .map(|&(x, y)| (options.round(x), options.round(y)))
+                .collect::<Vec<_>>();
+            let is_line = matches!(command.kind, 'L' | 'H' | 'V');
+            let kind = if is_line && options.shorthand_lines {
+                if end.1 == pos.1 {
+                    'H'
+                } else if end.0 == pos.0 {
+                    'V'
+                } else {
+                    'L'
+                }
+            } else {
+                command.kind
+            };
+            let tokens = |relative: bool| -> (Vec<String>, Vec<bool>) {
+                let offset = |p: (f64, f64)| {
+                    if relative {
+                        (options.round(p.0 - pos.0), options.round(p.1 - pos.1))
+                    } else {
+                        p
+                    }
+                };
+                // Leading scalar values, then coordinate pairs.
+                let (scalars, points) = match kind {
+                    'Z' => (vec![], vec![]),
+                    'H' => (vec![options.number(offset(end).0)], vec![]),
+                    'V' => (vec![options.number(offset(end).1)], vec![]),
+                    'A' => {
+                        let (rx, ry, angle, large, sweep) = command.arc.unwrap();
+                        let mut scalars = [rx, ry, angle]
+                            .iter()
+                            .map(|&v| options.number(options.round(v as f64)))
+                            .collect::<Vec<_>>();
+                        scalars.push((large as u8).to_string());
+                        scalars.push((sweep as u8).to_string());
+                        (scalars, vec![end])
+                    }
+                    _ => {
+                        let mut points = controls.clone();
+                        points.push(end);
+                        (vec![], points)
+                    }
+                };
+                let mut pairs = vec![false; scalars.len()];
+                let mut tokens = scalars;
+                for p in points {
+                    let p = offset(p);
+                    tokens.push(options.number(p.0));
+                    tokens.push(options.number(p.1));
+                    pairs.extend([false, true]);
+                }
+                (tokens, pairs)
+            };
+            let relative = match options.coordinates {
+                CoordinateMode::Preserve => command.relative,
+                CoordinateMode::Absolute => false,
+                CoordinateMode::Relative => true,
+                CoordinateMode::Shortest => {
+                    let (abs, abs_pairs) = tokens(false);
+                    let (rel, rel_pairs) = tokens(true);
+                    options.join(&rel, &rel_pairs).len() < options.join(&abs, &abs_pairs).len()
+                }
+            };
+            let (numbers, pairs) = tokens(relative);
+            let body = options.join(&numbers, &pairs);
+            let letter = if relative {
+                kind.to_ascii_lowercase()
+            } else {
+                kind
+            };
+            let repeated = options.drop_repeated_commands
+                && last_letter == Some(letter)
+                && kind != 'M'
+                && kind != 'Z';
+            if repeated {
+                let first = numbers.first().map(String::as_str).unwrap_or("");
+                if options.separator != Separator::Minimal || needs_separator(&out, first) {
+                    out.push(' ');
+                }
+            } else {
+                if !out.is_empty() && options.separator != Separator::Minimal {
+                    out.push(' ');
+                }
+                out.push(letter);
+                if !body.is_empty() && options.separator != Separator::Minimal {
+                    out.push(' ');
+                }
+            }
+            out.push_str(&body);
+            last_letter = Some(letter);
+            pos = end;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_options() {
+        let shape =
+            PathShape::parse_from_str("M 10.25,10 L 20.5,10 L 20.5,-0.4 C 20,20 30,30 40,40 Z")
+                .unwrap();
+        assert_eq!(
+            shape.format(&PathFormat::default()),
+            "M 10.25,10 L 20.5,10 L 20.5,-0.4 C 20,20 30,30 40,40 Z"
+        );
+        assert_eq!(
+            shape.format(
+                &PathFormat::default()
+                    .with_precision(Some(1))
+                    .with_separator(Separator::Space)
+            ),
+            "M 10.3 10 L 20.5 10 L 20.5 -0.4 C 20 20 30 30 40 40 Z"
+        );
+        assert_eq!(
+            shape.format(&PathFormat::minified(1)),
+            "M10.3 10H20.5V-.4C20 20 30 30 40 40Z"
+        );
+    }
+
+    #[test]
+    fn test_shortest_coordinates() {
+        let shape = PathShape::parse_from_str("M 100,100 L 101.5,100 L 102,102.25").unwrap();
+        assert_eq!(
+            shape.format(&PathFormat::minified(1)),
+            "M100 100h1.5l.5 2.3"
+        );
+    }
+
+    #[test]
+    fn test_repeated_commands() {
+        let shape = PathShape::parse_from_str("M 0,0 L 1,2 L 3,-4 L 5,6").unwrap();
+        let format = PathFormat {
+            separator: Separator::Minimal,
+            drop_repeated_commands: true,
+            ..Default::default()
+        };
+        assert_eq!(shape.format(&format), "M0 0L1 2 3-4 5 6");
+    }
+}
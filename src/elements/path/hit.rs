@@ -0,0 +1,125 @@
+use crate::elements::path::segment::{cross, dot, length, sub, Scalar, DEFAULT_TOLERANCE};
+use crate::elements::path::PathShape;
+use crate::elements::value::FillRule;
+
+/// Winding number of `point` with respect to closed polylines, in either
+/// `f32` or `f64` coordinates.
+pub(crate) fn polyline_winding<T>(polylines: &[Vec<(T, T)>], point: (T, T)) -> i32
+where
+    T: Scalar + Default + PartialOrd,
+{
+    let zero = T::default();
+    let mut winding = 0;
+    for polyline in polylines {
+        let Some(&last) = polyline.last() else {
+            continue;
+        };
+        let mut prev = last;
+        for &p in polyline {
+            if prev.1 <= point.1 {
+                if p.1 > point.1 && is_left(prev, p, point) > zero {
+                    winding += 1;
+                }
+            } else if p.1 <= point.1 && is_left(prev, p, point) < zero {
+                winding -= 1;
+            }
+            prev = p;
+        }
+    }
+    winding
+}
+
+/// Positive if `p` lies left of the line through `a` and `b`.
+fn is_left<T: Scalar>(a: (T, T), b: (T, T), p: (T, T)) -> T {
+    cross(sub(b, a), sub(p, a))
+}
+
+/// Distance from `point` to the line segment `a`-`b`.
+pub(crate) fn segment_distance(a: (f32, f32), b: (f32, f32), point: (f32, f32)) -> f32 {
+    let ab = sub(b, a);
+    let len_sq = dot(ab, ab);
+    let t = if len_sq == 0. {
+        0.
+    } else {
+        (dot(sub(point, a), ab) / len_sq).clamp(0., 1.)
+    };
+    length(sub(point, (a.0 + ab.0 * t, a.1 + ab.1 * t)))
+}
+
+/// Smallest distance from `point` to any of the (open) polylines.
+pub(crate) fn polyline_distance(polylines: &[Vec<(f32, f32)>], point: (f32, f32)) -> f32 {
+    let mut result = f32::INFINITY;
+    for polyline in polylines {
+        if polyline.len() == 1 {
+            result = result.min(length(sub(point, polyline[0])));
+        }
+        for w in polyline.windows(2) {
+            result = result.min(segment_distance(w[0], w[1], point));
+        }
+    }
+    result
+}
+
+pub(crate) fn fill_rule_matches(fill_rule: FillRule, winding: i32) -> bool {
+    match fill_rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+impl PathShape {
+    /// Flattens every subpath into a polyline within `tolerance`.
+    pub fn flatten(&self, tolerance: f32) -> Vec<Vec<(f32, f32)>> {
+        self.subpaths()
+            .iter()
+            .map(|s| s.flatten(tolerance))
+            .collect()
+    }
+
+    /// Winding number of `point`, treating every subpath as implicitly closed.
+    pub fn winding(&self, point: (f32, f32)) -> i32 {
+        polyline_winding(&self.flatten(DEFAULT_TOLERANCE), point)
+    }
+
+    /// Returns `true` if `point` lies inside the filled area of the path.
+    pub fn contains(&self, point: (f32, f32), fill_rule: FillRule) -> bool {
+        fill_rule_matches(fill_rule, self.winding(point))
+    }
+
+    /// Returns `true` if `point` lies on the stroke of the given width.
+    ///
+    /// Joins and caps are approximated as round.
+    pub fn stroke_contains(&self, point: (f32, f32), width: f32) -> bool {
+        polyline_distance(&self.flatten(DEFAULT_TOLERANCE), point) <= width / 2.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_fill_rule() {
+        // Two nested squares drawn in the same direction.
+        let shape =
+            PathShape::parse_from_str("M 0,0 L 10,0 L 10,10 L 0,10 Z M 2,2 L 8,2 L 8,8 L 2,8 Z")
+                .unwrap();
+        assert!(shape.contains((1., 1.), FillRule::NonZero));
+        assert!(shape.contains((5., 5.), FillRule::NonZero));
+        assert!(shape.contains((1., 1.), FillRule::EvenOdd));
+        assert!(!shape.contains((5., 5.), FillRule::EvenOdd));
+        assert!(!shape.contains((11., 5.), FillRule::NonZero));
+
+        let circle = PathShape::parse_from_str("M 0,0 A 5 5 0 1 1 10,0 A 5 5 0 1 1 0,0 Z").unwrap();
+        assert!(circle.contains((5., 4.), FillRule::NonZero));
+        assert!(!circle.contains((9., 4.), FillRule::NonZero));
+    }
+
+    #[test]
+    fn test_stroke_contains() {
+        let shape = PathShape::parse_from_str("M 0,0 H 10").unwrap();
+        assert!(shape.stroke_contains((5., 0.9), 2.));
+        assert!(!shape.stroke_contains((5., 1.1), 2.));
+        assert!(!shape.contains((5., 0.), FillRule::NonZero));
+    }
+}
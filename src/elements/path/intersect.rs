@@ -0,0 +1,360 @@
+use crate::elements::path::segment::{Segment, Subpath};
+use crate::elements::path::PathShape;
+
+type Point = (f64, f64);
+
+/// Position on a path: the segment as indexed by [`PathShape::subpaths`] and
+/// the parameter along it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PathLocation {
+    pub subpath: usize,
+    pub segment: usize,
+    pub t: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Intersection {
+    pub point: (f32, f32),
+    pub first: PathLocation,
+    pub second: PathLocation,
+}
+
+/// A cubic piece of a segment covering the parameter range `t0..t1`.
+#[derive(Copy, Clone, Debug)]
+struct Piece {
+    points: [Point; 4],
+    t0: f64,
+    t1: f64,
+}
+
+const FLATNESS: f64 = 1e-5;
+const MAX_DEPTH: usize = 48;
+
+impl Piece {
+    fn split(&self) -> (Piece, Piece) {
+        let mid = |a: Point, b: Point| ((a.0 + b.0) / 2., (a.1 + b.1) / 2.);
+        let [p0, p1, p2, p3] = self.points;
+        let (a, b, c) = (mid(p0, p1), mid(p1, p2), mid(p2, p3));
+        let (d, e) = (mid(a, b), mid(b, c));
+        let m = mid(d, e);
+        let tm = (self.t0 + self.t1) / 2.;
+        (
+            Piece {
+                points: [p0, a, d, m],
+                t0: self.t0,
+                t1: tm,
+            },
+            Piece {
+                points: [m, e, c, p3],
+                t0: tm,
+                t1: self.t1,
+            },
+        )
+    }
+
+    fn bounds(&self) -> (Point, Point) {
+        self.points.iter().fold(
+            (
+                (f64::INFINITY, f64::INFINITY),
+                (f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), p| {
+                (
+                    (min.0.min(p.0), min.1.min(p.1)),
+                    (max.0.max(p.0), max.1.max(p.1)),
+                )
+            },
+        )
+    }
+
+    fn chord_length(&self) -> f64 {
+        let [p0, _, _, p3] = self.points;
+        (p3.0 - p0.0).hypot(p3.1 - p0.1)
+    }
+
+    /// Largest distance of the control points from the chord.
+    fn flatness(&self) -> f64 {
+        let [p0, p1, p2, p3] = self.points;
+        let d = (p3.0 - p0.0, p3.1 - p0.1);
+        let len = d.0.hypot(d.1);
+        let dist = |p: Point| {
+            if len == 0. {
+                (p.0 - p0.0).hypot(p.1 - p0.1)
+            } else {
+                ((p.0 - p0.0) * d.1 - (p.1 - p0.1) * d.0).abs() / len
+            }
+        };
+        dist(p1).max(dist(p2))
+    }
+}
+
+fn pieces(segment: &Segment) -> Vec<Piece> {
+    let cubics = segment.to_cubics();
+    let count = cubics.len() as f64;
+    cubics
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let points = match c {
+                Segment::Cubic(p0, p1, p2, p3) => [p0, p1, p2, p3],
+                Segment::Line(p0, p3) => [p0, p0, p3, p3],
+                _ => unreachable!(),
+            };
+            Piece {
+                points: points.map(|(x, y)| (x as f64, y as f64)),
+                t0: i as f64 / count,
+                t1: (i + 1) as f64 / count,
+            }
+        })
+        .collect()
+}
+
+fn overlaps(a: (Point, Point), b: (Point, Point)) -> bool {
+    let eps = FLATNESS;
+    a.0 .0 <= b.1 .0 + eps
+        && b.0 .0 <= a.1 .0 + eps
+        && a.0 .1 <= b.1 .1 + eps
+        && b.0 .1 <= a.1 .1 + eps
+}
+
+fn chord_intersection(a: &Piece, b: &Piece) -> Option<(f64, f64)> {
+    let (p, q) = (a.points[0], a.points[3]);
+    let (c, d) = (b.points[0], b.points[3]);
+    let r = (q.0 - p.0, q.1 - p.1);
+    let s = (d.0 - c.0, d.1 - c.1);
+    let denom = r.0 * s.1 - r.1 * s.0;
+    if denom.abs() <= f64::EPSILON * r.0.hypot(r.1) * s.0.hypot(s.1) {
+        return None;
+    }
+    let qp = (c.0 - p.0, c.1 - p.1);
+    let t = (qp.0 * s.1 - qp.1 * s.0) / denom;
+    let u = (qp.0 * r.1 - qp.1 * r.0) / denom;
+    let slack = 1e-9;
+    if t < -slack || t > 1. + slack || u < -slack || u > 1. + slack {
+        return None;
+    }
+    let (t, u) = (t.clamp(0., 1.), u.clamp(0., 1.));
+    Some((a.t0 + (a.t1 - a.t0) * t, b.t0 + (b.t1 - b.t0) * u))
+}
+
+fn intersect_pieces(a: Piece, b: Piece, depth: usize, out: &mut Vec<(f64, f64)>) {
+    if !overlaps(a.bounds(), b.bounds()) {
+        return;
+    }
+    let (flat_a, flat_b) = (a.flatness() <= FLATNESS, b.flatness() <= FLATNESS);
+    if (flat_a && flat_b) || depth >= MAX_DEPTH {
+        out.extend(chord_intersection(&a, &b));
+        return;
+    }
+    if !flat_a && (flat_b || a.chord_length() >= b.chord_length()) {
+        let (a0, a1) = a.split();
+        intersect_pieces(a0, b, depth + 1, out);
+        intersect_pieces(a1, b, depth + 1, out);
+    } else {
+        let (b0, b1) = b.split();
+        intersect_pieces(a, b0, depth + 1, out);
+        intersect_pieces(a, b1, depth + 1, out);
+    }
+}
+
+fn dedup(mut params: Vec<(f64, f64)>) -> Vec<(f32, f32)> {
+    params.sort_by(|x, y| x.0.total_cmp(&y.0).then(x.1.total_cmp(&y.1)));
+    let mut result: Vec<(f64, f64)> = vec![];
+    for p in params {
+        if !result
+            .iter()
+            .any(|r| (r.0 - p.0).abs() < 1e-4 && (r.1 - p.1).abs() < 1e-4)
+        {
+            result.push(p);
+        }
+    }
+    result
+        .into_iter()
+        .map(|(t, u)| (t as f32, u as f32))
+        .collect()
+}
+
+/// Newton steps on the exact segments from the parameters found on their
+/// cubic pieces, whose parameters differ from the angle parameter of arcs.
+/// Keeps `(t, u)` if the segments are parallel there or a step does not help.
+fn refine(a: &Segment, b: &Segment, (mut t, mut u): (f64, f64)) -> (f64, f64) {
+    let gap = |t: f64, u: f64| {
+        let (p, q) = (a.eval(t as f32), b.eval(u as f32));
+        ((p.0 - q.0) as f64, (p.1 - q.1) as f64)
+    };
+    let mut d = gap(t, u);
+    for _ in 0..8 {
+        let (da, db) = (a.derivative(t as f32), b.derivative(u as f32));
+        let (da, db) = ((da.0 as f64, da.1 as f64), (db.0 as f64, db.1 as f64));
+        let det = db.0 * da.1 - da.0 * db.1;
+        if det.abs() <= 1e-12 {
+            break;
+        }
+        let next_t = (t + (d.0 * db.1 - db.0 * d.1) / det).clamp(0., 1.);
+        let next_u = (u + (d.0 * da.1 - da.0 * d.1) / det).clamp(0., 1.);
+        let next = gap(next_t, next_u);
+        if next.0.hypot(next.1) >= d.0.hypot(d.1) {
+            break;
+        }
+        (t, u, d) = (next_t, next_u, next);
+    }
+    (t, u)
+}
+
+impl Segment {
+    /// Parameters `(t, u)` of every point where `self` at `t` meets `other` at `u`.
+    ///
+    /// Overlapping collinear parts are not reported.
+    pub fn intersections(&self, other: &Segment) -> Vec<(f32, f32)> {
+        let mut params = vec![];
+        for a in pieces(self) {
+            for b in pieces(other) {
+                intersect_pieces(a, b, 0, &mut params);
+            }
+        }
+        dedup(params.into_iter().map(|p| refine(self, other, p)).collect())
+    }
+
+    /// Parameter pairs `(t, u)` with `t < u` where the segment crosses itself.
+    pub fn self_intersections(&self) -> Vec<(f32, f32)> {
+        let Segment::Cubic(..) = self else {
+            return vec![];
+        };
+        let mut params = vec![];
+        let (a, b) = pieces(self)[0].split();
+        intersect_pieces(a, b, 0, &mut params);
+        dedup(params)
+            .into_iter()
+            .filter(|(t, u)| (u - t).abs() > 1e-3)
+            .collect()
+    }
+}
+
+fn indexed_segments(subpaths: &[Subpath]) -> Vec<(usize, usize, &Segment)> {
+    subpaths
+        .iter()
+        .enumerate()
+        .flat_map(|(i, s)| {
+            s.segments
+                .iter()
+                .enumerate()
+                .map(move |(j, seg)| (i, j, seg))
+        })
+        .collect()
+}
+
+fn location(subpath: usize, segment: usize, t: f32) -> PathLocation {
+    PathLocation {
+        subpath,
+        segment,
+        t,
+    }
+}
+
+impl PathShape {
+    /// All points where `self` and `other` intersect.
+    pub fn intersections(&self, other: &PathShape) -> Vec<Intersection> {
+        let (first, second) = (self.subpaths(), other.subpaths());
+        let mut result = vec![];
+        for (i, j, a) in indexed_segments(&first) {
+            for (k, l, b) in indexed_segments(&second) {
+                for (t, u) in a.intersections(b) {
+                    result.push(Intersection {
+                        point: a.eval(t),
+                        first: location(i, j, t),
+                        second: location(k, l, u),
+                    });
+                }
+            }
+        }
+        result
+    }
+
+    /// All points where the path crosses itself, excluding the joints between
+    /// consecutive segments.
+    pub fn self_intersections(&self) -> Vec<Intersection> {
+        let subpaths = self.subpaths();
+        let segments = indexed_segments(&subpaths);
+        let mut result = vec![];
+        for (n, &(i, j, a)) in segments.iter().enumerate() {
+            for (t, u) in a.self_intersections() {
+                result.push(Intersection {
+                    point: a.eval(t),
+                    first: location(i, j, t),
+                    second: location(i, j, u),
+                });
+            }
+            for &(k, l, b) in &segments[n + 1..] {
+                let last = subpaths[i].segments.len() - 1;
+                let adjacent = i == k && l == j + 1;
+                let closing = i == k && subpaths[i].closed && j == 0 && l == last;
+                for (t, u) in a.intersections(b) {
+                    let joint = (adjacent && t > 1. - 1e-4 && u < 1e-4)
+                        || (closing && t < 1e-4 && u > 1. - 1e-4);
+                    if !joint {
+                        result.push(Intersection {
+                            point: a.eval(t),
+                            first: location(i, j, t),
+                            second: location(k, l, u),
+                        });
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    #[test]
+    fn test_line_circle_intersections() {
+        let line = PathShape::parse_from_str("M -10,0 H 10").unwrap();
+        let circle =
+            PathShape::parse_from_str("M -5,0 A 5 5 0 0 1 5,0 A 5 5 0 0 1 -5,0 Z").unwrap();
+        let mut points = line
+            .intersections(&circle)
+            .iter()
+            .map(|i| i.point)
+            .collect::<Vec<_>>();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        points.dedup_by(|a, b| close(*a, *b));
+        assert_eq!(points.len(), 2);
+        assert!(close(points[0], (-5., 0.)) && close(points[1], (5., 0.)));
+    }
+
+    #[test]
+    fn test_arc_parameters() {
+        let arc = PathShape::parse_from_str("M 100,0 A 100 100 0 0 1 -100,0").unwrap();
+        let line = PathShape::parse_from_str("M -200,20 H 200").unwrap();
+        let x = 96f32.sqrt() * 10.;
+        let found = arc.intersections(&line);
+        assert_eq!(found.len(), 2);
+        let (arc, line) = (&arc.subpaths()[0], &line.subpaths()[0]);
+        for intersection in found {
+            let on_arc = arc.segments[intersection.first.segment].eval(intersection.first.t);
+            let on_line = line.segments[intersection.second.segment].eval(intersection.second.t);
+            assert!(close(intersection.point, on_line), "{:?}", intersection);
+            assert!(close(on_arc, on_line), "{:?} != {:?}", on_arc, on_line);
+            assert!(close(on_arc, (x, 20.)) || close(on_arc, (-x, 20.)));
+        }
+    }
+
+    #[test]
+    fn test_self_intersections() {
+        let bowtie = PathShape::parse_from_str("M 0,0 L 10,10 L 10,0 L 0,10 Z").unwrap();
+        let found = bowtie.self_intersections();
+        assert_eq!(found.len(), 1);
+        assert!(close(found[0].point, (5., 5.)));
+        assert_eq!((found[0].first.segment, found[0].second.segment), (0, 2));
+
+        let looped = PathShape::parse_from_str("M 0,0 C 20,20 -10,20 10,0").unwrap();
+        assert_eq!(looped.self_intersections().len(), 1);
+    }
+}
@@ -0,0 +1,283 @@
+use crate::elements::path::segment::{scale, sub, Segment, Subpath};
+use crate::elements::path::{PathCommand, PathCommandKind, PathShape};
+
+/// Drawing direction of a closed subpath as seen on screen, where the y-axis
+/// points down.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Five point Gauss-Legendre nodes and weights on `[0, 1]`.
+const GAUSS: [(f32, f32); 5] = [
+    (0.046_910_077, 0.118_463_44),
+    (0.230_765_35, 0.239_314_34),
+    (0.5, 0.284_444_45),
+    (0.769_234_65, 0.239_314_34),
+    (0.953_089_9, 0.118_463_44),
+];
+
+/// Integrates `(x y' - y x') / 2`, `x² y' / 2` and `-y² x' / 2` along the segment,
+/// which by Green's theorem yield area and first moments of the enclosed region.
+fn moments(segment: &Segment) -> (f32, f32, f32) {
+    // Polynomial segments are integrated exactly; arcs are split into short
+    // pieces on which the rule is accurate enough.
+    let pieces = match segment {
+        Segment::Arc(_) => 8,
+        _ => 1,
+    };
+    let step = 1. / pieces as f32;
+    let mut result = (0., 0., 0.);
+    for i in 0..pieces {
+        for &(t, w) in &GAUSS {
+            let t = (i as f32 + t) * step;
+            let (x, y) = segment.eval(t);
+            let (dx, dy) = scale(segment.derivative(t), step);
+            result.0 += w * (x * dy - y * dx) / 2.;
+            result.1 += w * x * x * dy / 2.;
+            result.2 -= w * y * y * dx / 2.;
+        }
+    }
+    result
+}
+
+impl Subpath {
+    fn moments(&self) -> (f32, f32, f32) {
+        let closing = (self.end() != self.start).then(|| Segment::Line(self.end(), self.start));
+        self.segments
+            .iter()
+            .chain(closing.iter())
+            .map(moments)
+            .fold((0., 0., 0.), |acc, m| {
+                (acc.0 + m.0, acc.1 + m.1, acc.2 + m.2)
+            })
+    }
+
+    /// Area enclosed by the subpath, treating it as closed. Positive values
+    /// mean the subpath runs clockwise on screen.
+    pub fn signed_area(&self) -> f32 {
+        self.moments().0
+    }
+
+    /// Center of mass of the enclosed area, or `None` if the area is zero.
+    pub fn centroid(&self) -> Option<(f32, f32)> {
+        let (area, mx, my) = self.moments();
+        (area.abs() > f32::EPSILON).then(|| (mx / area, my / area))
+    }
+
+    pub fn orientation(&self) -> Option<Orientation> {
+        let area = self.signed_area();
+        if area > f32::EPSILON {
+            Some(Orientation::Clockwise)
+        } else if area < -f32::EPSILON {
+            Some(Orientation::CounterClockwise)
+        } else {
+            None
+        }
+    }
+
+    /// The same subpath drawn in the opposite direction.
+    pub fn reversed(&self) -> Subpath {
+        let mut segments = self
+            .segments
+            .iter()
+            .rev()
+            .map(|s| s.reversed())
+            .collect::<Vec<_>>();
+        let mut start = self.end();
+        if self.closed {
+            // Keep the closing line implicit by starting where it started.
+            if let Some(Segment::Line(from, to)) = self.segments.last().copied() {
+                if to == self.start {
+                    segments.rotate_left(1);
+                    start = from;
+                }
+            }
+        }
+        Subpath {
+            start,
+            segments,
+            closed: self.closed,
+        }
+    }
+}
+
+/// Rewrites absolute cubic and quadratic commands whose first control point is
+/// the reflection of the previous one as their smooth shorthand.
+fn smooth_commands(commands: Vec<PathCommand>) -> Vec<PathCommand> {
+    let mut pos = (0., 0.);
+    let mut last_control: Option<(char, (f32, f32))> = None;
+    let close = |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4;
+    commands
+        .into_iter()
+        .map(|command| {
+            let reflected = last_control.map(|(k, c)| (k, sub(scale(pos, 2.), c)));
+            let (result, control) = match command.command {
+                PathCommandKind::CubicBezierCurve(c1, c2) => (
+                    match reflected {
+                        Some(('C', r)) if close(r, c1) => {
+                            PathCommand::cubic_bezier_curve_smooth(command.movement, c2)
+                        }
+                        _ => command.clone(),
+                    },
+                    Some(('C', c2)),
+                ),
+                PathCommandKind::QuadraticBezierCurve(c) => (
+                    match reflected {
+                        Some(('Q', r)) if close(r, c) => {
+                            PathCommand::quadratic_bezier_curve_smooth(command.movement)
+                        }
+                        _ => command.clone(),
+                    },
+                    Some(('Q', c)),
+                ),
+                _ => (command.clone(), None),
+            };
+            pos = command.movement;
+            last_control = control;
+            result
+        })
+        .collect()
+}
+
+impl PathShape {
+    /// Sum of the signed areas of all subpaths.
+    pub fn signed_area(&self) -> f32 {
+        self.subpaths().iter().map(|s| s.signed_area()).sum()
+    }
+
+    /// Area weighted center of all subpaths, or `None` if the total area is zero.
+    pub fn centroid(&self) -> Option<(f32, f32)> {
+        let (area, mx, my) = self
+            .subpaths()
+            .iter()
+            .map(|s| s.moments())
+            .fold((0., 0., 0.), |acc, m| {
+                (acc.0 + m.0, acc.1 + m.1, acc.2 + m.2)
+            });
+        (area.abs() > f32::EPSILON).then(|| (mx / area, my / area))
+    }
+
+    pub fn orientations(&self) -> Vec<Option<Orientation>> {
+        self.subpaths().iter().map(|s| s.orientation()).collect()
+    }
+
+    /// Reverses the drawing direction of every subpath. The result uses
+    /// absolute commands; smooth curves stay smooth where the input used them.
+    pub fn reverse(&self) -> PathShape {
+        let subpaths = self
+            .subpaths()
+            .iter()
+            .filter(|s| !s.segments.is_empty() || s.closed)
+            .map(|s| s.reversed())
+            .collect::<Vec<_>>();
+        let shape = PathShape::from_subpaths(&subpaths);
+        let smooth = self.elements.iter().any(|c| {
+            matches!(
+                c.command,
+                PathCommandKind::CubicBezierCurveSmooth(_)
+                    | PathCommandKind::QuadraticBezierCurveSmooth
+            )
+        });
+        if smooth {
+            PathShape {
+                elements: smooth_commands(shape.elements),
+            }
+        } else {
+            shape
+        }
+    }
+
+    /// Splits the path into one path per subpath, keeping the original
+    /// commands. Each part starts with an absolute move-to.
+    pub fn split_subpaths(&self) -> Vec<PathShape> {
+        let starts = self.subpaths().iter().map(|s| s.start).collect::<Vec<_>>();
+        let mut parts: Vec<PathShape> = vec![];
+        let mut after_close = true;
+        for command in &self.elements {
+            let is_move = command.command == PathCommandKind::MoveTo;
+            if is_move || after_close {
+                let start = starts[parts.len().min(starts.len() - 1)];
+                parts.push(PathShape::new().with_commands(vec![PathCommand::move_to(start)]));
+            }
+            after_close = command.command == PathCommandKind::ClosePath;
+            if !is_move {
+                parts.last_mut().unwrap().elements.push(command.clone());
+            }
+        }
+        parts
+    }
+
+    /// Appends the subpaths of `other` to this path.
+    pub fn append(&mut self, other: &PathShape) {
+        let mut elements = other.elements.iter().cloned();
+        match other.elements.first() {
+            Some(first) if first.command == PathCommandKind::MoveTo => {
+                // A leading relative move-to is relative to the origin.
+                let mut first = elements.next().unwrap();
+                first.relative = false;
+                self.elements.push(first);
+            }
+            Some(_) => self.elements.push(PathCommand::move_to((0., 0.))),
+            None => {}
+        }
+        self.elements.extend(elements);
+    }
+
+    /// Concatenates several paths into one.
+    pub fn join<'a>(paths: impl IntoIterator<Item = &'a PathShape>) -> PathShape {
+        let mut result = PathShape::new();
+        for path in paths {
+            result.append(path);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-2
+    }
+
+    #[test]
+    fn test_area_centroid_orientation() {
+        let square = PathShape::parse_from_str("M 0,0 H 10 V 10 H 0 Z").unwrap();
+        assert!(close(square.signed_area(), 100.));
+        assert_eq!(square.orientations(), vec![Some(Orientation::Clockwise)]);
+        let (cx, cy) = square.centroid().unwrap();
+        assert!(close(cx, 5.) && close(cy, 5.));
+
+        let circle = PathShape::parse_from_str("M 0,5 A 5 5 0 0 0 10,5 A 5 5 0 0 0 0,5 Z").unwrap();
+        assert!(close(circle.signed_area(), -std::f32::consts::PI * 25.));
+        let (cx, cy) = circle.centroid().unwrap();
+        assert!(close(cx, 5.) && close(cy, 5.));
+
+        let reversed = square.reverse();
+        assert_eq!(reversed.to_string(), "M 0,10\nL 10,10\nL 10,0\nL 0,0\nZ");
+        assert!(close(reversed.signed_area(), -100.));
+    }
+
+    #[test]
+    fn test_reverse_smooth() {
+        let shape = PathShape::parse_from_str("M 0,0 C 0,10 10,10 10,0 S 20,-10 20,0").unwrap();
+        let reversed = shape.reverse();
+        assert_eq!(
+            reversed.to_string(),
+            "M 20,0\nC 20,-10 10,-10 10,0\nS 0,10 0,0"
+        );
+        assert_eq!(reversed.reverse().subpaths(), shape.subpaths());
+    }
+
+    #[test]
+    fn test_split_and_join() {
+        let shape = PathShape::parse_from_str("M 0,0 L 10,0 Z m 5,5 l 1,1").unwrap();
+        let parts = shape.split_subpaths();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1].to_string(), "M 5,5\nl 1,1");
+        assert_eq!(PathShape::join(&parts).subpaths(), shape.subpaths());
+    }
+}
@@ -0,0 +1,333 @@
+use crate::elements::animate::Animate;
+use crate::elements::attribute::{AttributeName, Dur};
+use crate::elements::path::segment::{bounds_of, lerp, Segment, Subpath};
+use crate::elements::path::PathShape;
+
+type Cubic = [(f32, f32); 4];
+
+/// A subpath made only of cubic curves.
+#[derive(Clone, Debug)]
+struct CubicPath {
+    start: (f32, f32),
+    cubics: Vec<Cubic>,
+    closed: bool,
+}
+
+impl CubicPath {
+    fn from_subpath(subpath: &Subpath) -> Self {
+        let cubics = subpath
+            .segments
+            .iter()
+            .flat_map(|s| s.to_cubics())
+            .map(|c| match c {
+                Segment::Cubic(p0, c1, c2, p1) => [p0, c1, c2, p1],
+                _ => unreachable!(),
+            })
+            .collect();
+        Self {
+            start: subpath.start,
+            cubics,
+            closed: subpath.closed,
+        }
+    }
+
+    /// Subpath collapsed into `point`, for growing out of nothing.
+    fn point(point: (f32, f32), closed: bool) -> Self {
+        Self {
+            start: point,
+            cubics: vec![],
+            closed,
+        }
+    }
+
+    fn center(&self) -> (f32, f32) {
+        let points = self
+            .cubics
+            .iter()
+            .flatten()
+            .copied()
+            .chain([self.start])
+            .collect::<Vec<_>>();
+        let (min, max) = bounds_of(&points).unwrap_or((self.start, self.start));
+        lerp(min, max, 0.5)
+    }
+
+    /// Splits the longest curves in half until there are `count` of them.
+    fn split_to(&mut self, count: usize) {
+        if self.cubics.is_empty() && count > 0 {
+            let p = self.start;
+            self.cubics.push([p, p, p, p]);
+        }
+        while self.cubics.len() < count {
+            let (index, _) = self
+                .cubics
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i, polygon_length(c)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            let (first, second) = match Segment::Cubic(
+                self.cubics[index][0],
+                self.cubics[index][1],
+                self.cubics[index][2],
+                self.cubics[index][3],
+            )
+            .split(0.5)
+            {
+                (Segment::Cubic(a0, a1, a2, a3), Segment::Cubic(b0, b1, b2, b3)) => {
+                    ([a0, a1, a2, a3], [b0, b1, b2, b3])
+                }
+                _ => unreachable!(),
+            };
+            self.cubics[index] = first;
+            self.cubics.insert(index + 1, second);
+        }
+    }
+
+    /// Redraws a closed subpath in the orientation of `target`, so blending
+    /// does not turn it inside out.
+    fn orient_to(&mut self, target: &CubicPath) {
+        if !self.closed || !target.closed {
+            return;
+        }
+        let (subpath, target) = (self.to_subpath(), target.to_subpath());
+        if let (Some(a), Some(b)) = (subpath.orientation(), target.orientation()) {
+            if a != b {
+                *self = CubicPath::from_subpath(&subpath.reversed());
+            }
+        }
+    }
+
+    /// Rotates the curves of a closed subpath so that its start lines up with
+    /// `target` as closely as possible.
+    fn align_to(&mut self, target: &CubicPath) {
+        if !self.closed || !target.closed || self.cubics.len() != target.cubics.len() {
+            return;
+        }
+        let distance = |offset: usize| -> f32 {
+            let n = self.cubics.len();
+            (0..n)
+                .map(|i| {
+                    let (a, b) = (self.cubics[(i + offset) % n][0], target.cubics[i][0]);
+                    (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)
+                })
+                .sum()
+        };
+        let best = (0..self.cubics.len())
+            .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(0);
+        self.cubics.rotate_left(best);
+        if let Some(first) = self.cubics.first() {
+            self.start = first[0];
+        }
+    }
+
+    /// Draws the closing line of a closed subpath as a curve, or adds a
+    /// zero-length curve to an open one, and closes both. Subpaths closed
+    /// this way share their commands whether they were closed or not.
+    fn close_explicitly(&mut self) {
+        let end = self.cubics.last().map_or(self.start, |c| c[3]);
+        let to = if self.closed { self.start } else { end };
+        self.cubics
+            .push([end, lerp(end, to, 1. / 3.), lerp(end, to, 2. / 3.), to]);
+        self.closed = true;
+    }
+
+    fn to_subpath(&self) -> Subpath {
+        Subpath {
+            start: self.start,
+            segments: self
+                .cubics
+                .iter()
+                .map(|c| Segment::Cubic(c[0], c[1], c[2], c[3]))
+                .collect(),
+            closed: self.closed,
+        }
+    }
+}
+
+fn polygon_length(c: &Cubic) -> f32 {
+    c.windows(2)
+        .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+        .sum()
+}
+
+/// Brings all shapes to the same structure: equally many subpaths, each with
+/// equally many cubic curves.
+fn compatible(shapes: &[&PathShape]) -> Vec<Vec<CubicPath>> {
+    let mut all = shapes
+        .iter()
+        .map(|s| {
+            s.subpaths()
+                .iter()
+                .map(CubicPath::from_subpath)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let count = all.iter().map(|s| s.len()).max().unwrap_or(0);
+    for i in 0..count {
+        // Subpaths missing from a shape grow out of the center of the first
+        // shape that has them.
+        let Some(reference) = all.iter().find_map(|s| s.get(i)).cloned() else {
+            continue;
+        };
+        for subpaths in all.iter_mut() {
+            if subpaths.len() <= i {
+                subpaths.push(CubicPath::point(reference.center(), reference.closed));
+            }
+        }
+        let first = all[0][i].clone();
+        for subpaths in all.iter_mut().skip(1) {
+            subpaths[i].orient_to(&first);
+        }
+        let segments = all.iter().map(|s| s[i].cubics.len()).max().unwrap_or(0);
+        for subpaths in all.iter_mut() {
+            subpaths[i].split_to(segments);
+        }
+        let first = all[0][i].clone();
+        for subpaths in all.iter_mut().skip(1) {
+            subpaths[i].align_to(&first);
+        }
+    }
+    all
+}
+
+fn to_shape(subpaths: &[CubicPath]) -> PathShape {
+    PathShape::from_subpaths(&subpaths.iter().map(|s| s.to_subpath()).collect::<Vec<_>>())
+}
+
+/// Path data on a single line, as used in animation value lists.
+fn path_data(shape: &PathShape) -> String {
+    shape
+        .elements
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl PathShape {
+    /// Rewrites both paths with the same number of subpaths and cubic curves,
+    /// so they can be blended point by point.
+    pub fn make_compatible(&self, other: &PathShape) -> (PathShape, PathShape) {
+        let all = compatible(&[self, other]);
+        (to_shape(&all[0]), to_shape(&all[1]))
+    }
+
+    /// Blends between `self` at `t = 0` and `other` at `t = 1`. Whether a
+    /// subpath is closed switches to that of `other` at `t = 1`.
+    pub fn interpolate(&self, other: &PathShape, t: f32) -> PathShape {
+        let all = compatible(&[self, other]);
+        let blended = all[0]
+            .iter()
+            .zip(&all[1])
+            .map(|(a, b)| CubicPath {
+                start: lerp(a.start, b.start, t),
+                cubics: a
+                    .cubics
+                    .iter()
+                    .zip(&b.cubics)
+                    .map(|(ca, cb)| [0, 1, 2, 3].map(|k| lerp(ca[k], cb[k], t)))
+                    .collect(),
+                closed: if t < 1. { a.closed } else { b.closed },
+            })
+            .collect::<Vec<_>>();
+        to_shape(&blended)
+    }
+
+    /// SMIL animation of the `d` attribute through `shapes`, with the path
+    /// data made compatible so that user agents can interpolate it. A
+    /// subpath closed in any shape is closed in all of them, which draws a
+    /// line back to the start of the open ones.
+    pub fn morph_animation(shapes: &[PathShape], dur: Dur) -> Animate {
+        let mut all = compatible(&shapes.iter().collect::<Vec<_>>());
+        let count = all.first().map_or(0, |s| s.len());
+        for i in 0..count {
+            if all.iter().any(|s| s[i].closed) {
+                for subpaths in all.iter_mut() {
+                    subpaths[i].close_explicitly();
+                }
+            }
+        }
+        let values = all
+            .iter()
+            .map(|s| path_data(&to_shape(s)))
+            .collect::<Vec<_>>();
+        Animate::new(AttributeName("d".to_string()), &values, dur)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::value::ClockValue;
+    use crate::SvgElement;
+    use std::time::Duration;
+
+    #[test]
+    fn test_interpolate() {
+        let square = PathShape::parse_from_str("M 0,0 H 10 V 10 H 0 Z").unwrap();
+        let triangle = PathShape::parse_from_str("M 0,0 L 20,0 L 0,20 Z").unwrap();
+        let (a, b) = square.make_compatible(&triangle);
+        assert_eq!(a.subpaths()[0].segments.len(), 4);
+        assert_eq!(b.subpaths()[0].segments.len(), 4);
+
+        assert_eq!(square.interpolate(&triangle, 0.).subpaths(), a.subpaths());
+        let half = square.interpolate(&triangle, 0.5);
+        assert_eq!(half.subpaths()[0].segments[0].end(), (15., 0.));
+
+        let one = PathShape::parse_from_str("M 0,0 H 10 V 10 H 0 Z").unwrap();
+        let two =
+            PathShape::parse_from_str("M 0,0 H 10 V 10 H 0 Z M 20,20 H 30 V 30 H 20 Z").unwrap();
+        let grown = one.interpolate(&two, 0.);
+        assert_eq!(grown.subpaths().len(), 2);
+        assert_eq!(grown.subpaths()[1].start, (25., 25.));
+    }
+
+    #[test]
+    fn test_interpolate_orientation_and_closing() {
+        let clockwise = PathShape::parse_from_str("M 0,0 H 10 V 10 H 0 Z").unwrap();
+        let counter = PathShape::parse_from_str("M 0,0 V 10 H 10 V 0 Z").unwrap();
+        let half = clockwise.interpolate(&counter, 0.5);
+        assert!((half.subpaths()[0].signed_area() - 100.).abs() < 1e-3);
+
+        let open = PathShape::parse_from_str("M 0,0 H 10 V 10").unwrap();
+        assert!(!open.interpolate(&clockwise, 0.5).subpaths()[0].closed);
+        assert!(open.interpolate(&clockwise, 1.).subpaths()[0].closed);
+        assert!(clockwise.interpolate(&open, 0.5).subpaths()[0].closed);
+    }
+
+    #[test]
+    fn test_morph_animation() {
+        let a = PathShape::parse_from_str("M 0,0 L 10,0").unwrap();
+        let b = PathShape::parse_from_str("M 0,0 L 10,0 L 10,10").unwrap();
+        let animate = PathShape::morph_animation(
+            &[a, b],
+            Dur::ClockValue(ClockValue::from(Duration::from_secs(2))),
+        );
+        assert_eq!(animate.name(), "animate");
+        assert_eq!(animate.attributes()["attributeName"], "d");
+        assert_eq!(animate.attributes()["dur"], "00:00:02");
+        let values = animate.values();
+        assert_eq!(values.len(), 2);
+        assert_eq!(
+            values[0].matches('C').count(),
+            values[1].matches('C').count()
+        );
+
+        let closed = PathShape::parse_from_str("M 0,0 H 10 V 10 Z").unwrap();
+        let open = PathShape::parse_from_str("M 0,0 L 10,0").unwrap();
+        let animate = PathShape::morph_animation(
+            &[open.clone(), closed, open],
+            Dur::ClockValue(ClockValue::from(Duration::from_secs(2))),
+        );
+        let letters = |d: &str| {
+            d.chars()
+                .filter(|c| c.is_ascii_alphabetic())
+                .collect::<String>()
+        };
+        let values = animate.values();
+        assert!(letters(values[0]).ends_with('Z'));
+        assert!(values.iter().all(|v| letters(v) == letters(values[0])));
+    }
+}
@@ -0,0 +1,349 @@
+use crate::elements::attribute::{StrokeLinecap, StrokeLinejoin};
+use crate::elements::path::boolean::{
+    boolean_polygons, flatten, inside, scale_of, to_shape, Point,
+};
+use crate::elements::path::hit::polyline_winding;
+use crate::elements::path::segment::{add, dot, scale, DEFAULT_TOLERANCE};
+use crate::elements::path::PathShape;
+use crate::elements::value::FillRule;
+use std::f64::consts::PI;
+
+fn direction(a: Point, b: Point) -> Point {
+    let d = (b.0 - a.0, b.1 - a.1);
+    let len = d.0.hypot(d.1);
+    (d.0 / len, d.1 / len)
+}
+
+/// Right-hand normal of a unit direction.
+fn normal(u: Point) -> Point {
+    (u.1, -u.0)
+}
+
+fn signed_area(ring: &[Point]) -> f64 {
+    (0..ring.len())
+        .map(|i| {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum::<f64>()
+        / 2.
+}
+
+/// Pushes points on the circle of `radius` around `center`, starting at
+/// `start` radians and sweeping `sweep` radians, excluding the first point.
+fn arc_points(
+    center: Point,
+    radius: f64,
+    start: f64,
+    sweep: f64,
+    tolerance: f64,
+    out: &mut Vec<Point>,
+) {
+    let step = 2. * (1. - (tolerance / radius).min(1.)).acos();
+    let count = ((sweep.abs() / step.max(1e-3)).ceil() as usize).clamp(1, 256);
+    for i in 1..=count {
+        let angle = start + sweep * i as f64 / count as f64;
+        out.push(add(center, (radius * angle.cos(), radius * angle.sin())));
+    }
+}
+
+fn circle(center: Point, radius: f64, tolerance: f64) -> Vec<Point> {
+    let mut points = vec![(center.0 + radius, center.1)];
+    arc_points(center, radius, 0., 2. * PI, tolerance, &mut points);
+    points.pop();
+    points
+}
+
+/// Options shared by stroking and offsetting.
+struct Stroker {
+    half_width: f64,
+    cap: StrokeLinecap,
+    join: StrokeLinejoin,
+    miter_limit: f64,
+    tolerance: f64,
+}
+
+impl Stroker {
+    /// Wedge filling the outer side of the corner at `p` between the
+    /// directions `u0` and `u1`, or `None` if the corner needs no join.
+    fn join(&self, p: Point, u0: Point, u1: Point) -> Option<Vec<Point>> {
+        let h = self.half_width;
+        let turn = u0.0 * u1.1 - u0.1 * u1.0;
+        let cos = u0.0 * u1.0 + u0.1 * u1.1;
+        if turn.abs() < 1e-12 && cos > 0. {
+            return None;
+        }
+        // The outer side is on the right of a left turn and vice versa.
+        let side = if turn >= 0. { 1. } else { -1. };
+        let (n0, n1) = (scale(normal(u0), h * side), scale(normal(u1), h * side));
+        let (a, b) = (add(p, n0), add(p, n1));
+        let mut wedge = vec![p, a];
+        match self.join {
+            StrokeLinejoin::Round => {
+                let sweep = if turn.abs() < 1e-12 {
+                    PI
+                } else {
+                    turn.atan2(cos)
+                };
+                arc_points(p, h, n0.1.atan2(n0.0), sweep, self.tolerance, &mut wedge);
+                wedge.pop();
+            }
+            StrokeLinejoin::Miter => {
+                if 1. + cos > 1e-12 && (2. / (1. + cos)).sqrt() <= self.miter_limit {
+                    wedge.push(add(p, scale(add(n0, n1), 1. / (1. + cos))));
+                }
+            }
+            // Flattened segments have no curvature, so arcs join like
+            // miter-clip.
+            StrokeLinejoin::MiterClip | StrokeLinejoin::Arcs => {
+                if 1. + cos > 1e-12 && (2. / (1. + cos)).sqrt() <= self.miter_limit {
+                    wedge.push(add(p, scale(add(n0, n1), 1. / (1. + cos))));
+                } else {
+                    // Cut the miter square to the bisector `m` at the limit.
+                    let bisector = add(n0, n1);
+                    let m = match bisector.0.hypot(bisector.1) > 1e-12 * h {
+                        true => direction((0., 0.), bisector),
+                        false => u0,
+                    };
+                    let d = self.miter_limit.max(1.) * h;
+                    let s0 = (d - dot(n0, m)) / dot(u0, m).max(1e-12);
+                    let s1 = (d - dot(n1, m)) / dot(u1, m).min(-1e-12);
+                    wedge.push(add(a, scale(u0, s0)));
+                    wedge.push(add(b, scale(u1, s1)));
+                }
+            }
+            StrokeLinejoin::Bevel => {}
+        }
+        wedge.push(b);
+        Some(wedge)
+    }
+
+    fn cap(&self, p: Point, u: Point) -> Option<Vec<Point>> {
+        let n = scale(normal(u), self.half_width);
+        let along = scale(u, self.half_width);
+        match self.cap {
+            StrokeLinecap::Butt => None,
+            StrokeLinecap::Round => Some(circle(p, self.half_width, self.tolerance)),
+            StrokeLinecap::Square => Some(vec![
+                add(p, n),
+                add(add(p, n), along),
+                add(add(p, scale(n, -1.)), along),
+                add(p, scale(n, -1.)),
+            ]),
+        }
+    }
+
+    /// Decomposes the stroke of a polyline into simple convex-ish pieces whose
+    /// union is the stroke outline.
+    fn pieces(&self, points: &[Point], closed: bool, out: &mut Vec<Vec<Point>>) {
+        let mut points = points.to_vec();
+        points.dedup();
+        if closed && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 2 {
+            if let Some(&p) = points.first() {
+                let h = self.half_width;
+                match self.cap {
+                    StrokeLinecap::Butt => {}
+                    StrokeLinecap::Round => out.push(circle(p, h, self.tolerance)),
+                    StrokeLinecap::Square => out.push(vec![
+                        (p.0 - h, p.1 - h),
+                        (p.0 + h, p.1 - h),
+                        (p.0 + h, p.1 + h),
+                        (p.0 - h, p.1 + h),
+                    ]),
+                }
+            }
+            return;
+        }
+        let count = if closed {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+        let h = self.half_width;
+        for i in 0..count {
+            let (p, q) = (points[i], points[(i + 1) % points.len()]);
+            let n = scale(normal(direction(p, q)), h);
+            out.push(vec![
+                add(p, n),
+                add(q, n),
+                add(q, scale(n, -1.)),
+                add(p, scale(n, -1.)),
+            ]);
+        }
+        let corners = if closed {
+            0..points.len()
+        } else {
+            1..points.len() - 1
+        };
+        for i in corners {
+            let prev = points[(i + points.len() - 1) % points.len()];
+            let next = points[(i + 1) % points.len()];
+            let p = points[i];
+            out.extend(self.join(p, direction(prev, p), direction(p, next)));
+        }
+        if !closed {
+            let last = points.len() - 1;
+            out.extend(self.cap(points[last], direction(points[last - 1], points[last])));
+            out.extend(self.cap(points[0], direction(points[1], points[0])));
+        }
+    }
+
+    fn outline(&self, polylines: &[(Vec<Point>, bool)]) -> Vec<Vec<Point>> {
+        let mut pieces = vec![];
+        for (points, closed) in polylines {
+            self.pieces(points, *closed, &mut pieces);
+        }
+        for piece in &mut pieces {
+            if signed_area(piece) < 0. {
+                piece.reverse();
+            }
+        }
+        pieces.retain(|p| p.len() > 2 && signed_area(p).abs() > 1e-12);
+        pieces
+    }
+}
+
+impl PathShape {
+    /// Grows (positive `distance`) or shrinks (negative `distance`) the area
+    /// filled under the non-zero rule, using `join` at corners.
+    pub fn offset(&self, distance: f32, join: StrokeLinejoin) -> PathShape {
+        self.offset_with(distance, FillRule::NonZero, join, 4., DEFAULT_TOLERANCE)
+    }
+
+    pub fn offset_with(
+        &self,
+        distance: f32,
+        fill_rule: FillRule,
+        join: StrokeLinejoin,
+        miter_limit: f32,
+        tolerance: f32,
+    ) -> PathShape {
+        let polygons = flatten(self, tolerance);
+        let rings = boolean_polygons(
+            &polygons,
+            |p| inside(&polygons, fill_rule, p),
+            scale_of(&polygons),
+        );
+        if distance == 0. {
+            return to_shape(rings);
+        }
+        let stroker = Stroker {
+            half_width: distance.abs() as f64,
+            cap: StrokeLinecap::Butt,
+            join,
+            miter_limit: miter_limit as f64,
+            tolerance: tolerance as f64,
+        };
+        let pieces = stroker.outline(&rings.iter().map(|r| (r.clone(), true)).collect::<Vec<_>>());
+        let all = rings
+            .iter()
+            .chain(pieces.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        to_shape(boolean_polygons(
+            &all,
+            |p| {
+                let filled = polyline_winding(&rings, p) != 0;
+                let swept = polyline_winding(&pieces, p) != 0;
+                if distance > 0. {
+                    filled || swept
+                } else {
+                    filled && !swept
+                }
+            },
+            scale_of(&all),
+        ))
+    }
+
+    /// Converts the stroke of the path into an equivalent filled outline.
+    pub fn stroke_to_path(
+        &self,
+        width: f32,
+        linecap: StrokeLinecap,
+        linejoin: StrokeLinejoin,
+        miterlimit: f32,
+    ) -> PathShape {
+        self.stroke_to_path_with(width, linecap, linejoin, miterlimit, DEFAULT_TOLERANCE)
+    }
+
+    pub fn stroke_to_path_with(
+        &self,
+        width: f32,
+        linecap: StrokeLinecap,
+        linejoin: StrokeLinejoin,
+        miterlimit: f32,
+        tolerance: f32,
+    ) -> PathShape {
+        let stroker = Stroker {
+            half_width: width as f64 / 2.,
+            cap: linecap,
+            join: linejoin,
+            miter_limit: miterlimit as f64,
+            tolerance: tolerance as f64,
+        };
+        let polylines = self
+            .subpaths()
+            .iter()
+            .map(|s| {
+                let points = s
+                    .flatten(tolerance)
+                    .into_iter()
+                    .map(|(x, y)| (x as f64, y as f64))
+                    .collect::<Vec<_>>();
+                (points, s.closed)
+            })
+            .collect::<Vec<_>>();
+        let pieces = stroker.outline(&polylines);
+        to_shape(boolean_polygons(
+            &pieces,
+            |p| polyline_winding(&pieces, p) != 0,
+            scale_of(&pieces),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_square() {
+        let square = PathShape::parse_from_str("M 0,0 H 10 V 10 H 0 Z").unwrap();
+        let inside = |s: &PathShape, p| s.contains(p, FillRule::NonZero);
+
+        let mitered = square.offset(1., StrokeLinejoin::Miter);
+        assert!(inside(&mitered, (-0.9, -0.9)) && !inside(&mitered, (-1.1, 5.)));
+        let rounded = square.offset(1., StrokeLinejoin::Round);
+        assert!(!inside(&rounded, (-0.9, -0.9)) && inside(&rounded, (-0.5, -0.5)));
+        let shrunk = square.offset(-1., StrokeLinejoin::Miter);
+        assert!(inside(&shrunk, (1.5, 1.5)) && !inside(&shrunk, (0.5, 5.)));
+
+        let offset = |join| square.offset_with(1., FillRule::NonZero, join, 1., 0.05);
+        let beveled = offset(StrokeLinejoin::Miter);
+        assert!(!inside(&beveled, (-0.6, -0.6)));
+        for join in [StrokeLinejoin::MiterClip, StrokeLinejoin::Arcs] {
+            let clipped = offset(join);
+            assert!(inside(&clipped, (-0.6, -0.6)) && !inside(&clipped, (-0.8, -0.8)));
+        }
+    }
+
+    #[test]
+    fn test_stroke_to_path() {
+        let line = PathShape::parse_from_str("M 0,0 H 10").unwrap();
+        let butt = line.stroke_to_path(2., StrokeLinecap::Butt, StrokeLinejoin::Miter, 4.);
+        assert!(butt.contains((5., 0.9), FillRule::NonZero));
+        assert!(!butt.contains((10.5, 0.), FillRule::NonZero));
+        let square = line.stroke_to_path(2., StrokeLinecap::Square, StrokeLinejoin::Miter, 4.);
+        assert!(square.contains((10.5, 0.9), FillRule::NonZero));
+
+        let outline = PathShape::parse_from_str("M 0,0 H 10 V 10 H 0 Z")
+            .unwrap()
+            .stroke_to_path(2., StrokeLinecap::Butt, StrokeLinejoin::Miter, 4.);
+        assert_eq!(outline.subpaths().len(), 2);
+        assert!(outline.contains((-0.9, -0.9), FillRule::NonZero));
+        assert!(!outline.contains((5., 5.), FillRule::NonZero));
+    }
+}
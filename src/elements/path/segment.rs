@@ -0,0 +1,551 @@
+use crate::elements::path::{PathCommand, PathCommandKind, PathShape};
+use std::f32::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+/// Flattening tolerance used when no explicit tolerance is requested.
+pub const DEFAULT_TOLERANCE: f32 = 0.05;
+
+/// Coordinates the vector helpers below work on, so `f32` path geometry and
+/// the `f64` geometry of offsetting and fitting share them.
+pub(crate) trait Scalar:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Scalar for T {}
+
+pub(crate) fn add<T: Scalar>(a: (T, T), b: (T, T)) -> (T, T) {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+pub(crate) fn sub<T: Scalar>(a: (T, T), b: (T, T)) -> (T, T) {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+pub(crate) fn scale<T: Scalar>(a: (T, T), s: T) -> (T, T) {
+    (a.0 * s, a.1 * s)
+}
+
+pub(crate) fn dot<T: Scalar>(a: (T, T), b: (T, T)) -> T {
+    a.0 * b.0 + a.1 * b.1
+}
+
+pub(crate) fn cross<T: Scalar>(a: (T, T), b: (T, T)) -> T {
+    a.0 * b.1 - a.1 * b.0
+}
+
+pub(crate) fn length(a: (f32, f32)) -> f32 {
+    a.0.hypot(a.1)
+}
+
+pub(crate) fn lerp(a: (f32, f32), b: (f32, f32), t: f32) -> (f32, f32) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+/// An elliptical arc in endpoint parameterization, as written in path data.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Arc {
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+    pub rx: f32,
+    pub ry: f32,
+    pub angle: f32,
+    pub large_arc: bool,
+    pub sweep: bool,
+}
+
+/// Center parameterization of an [`Arc`] (SVG 2, appendix B.2.4).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ArcCenter {
+    pub center: (f32, f32),
+    pub rx: f32,
+    pub ry: f32,
+    /// x-axis rotation in radians.
+    pub phi: f32,
+    pub start_angle: f32,
+    pub sweep_angle: f32,
+}
+
+impl ArcCenter {
+    pub fn point_at_angle(&self, theta: f32) -> (f32, f32) {
+        let (sin_phi, cos_phi) = self.phi.sin_cos();
+        let (x, y) = (self.rx * theta.cos(), self.ry * theta.sin());
+        (
+            self.center.0 + cos_phi * x - sin_phi * y,
+            self.center.1 + sin_phi * x + cos_phi * y,
+        )
+    }
+
+    fn derivative_at_angle(&self, theta: f32) -> (f32, f32) {
+        let (sin_phi, cos_phi) = self.phi.sin_cos();
+        let (x, y) = (-self.rx * theta.sin(), self.ry * theta.cos());
+        (cos_phi * x - sin_phi * y, sin_phi * x + cos_phi * y)
+    }
+}
+
+impl Arc {
+    /// Returns `true` if the arc has to be treated as a straight line.
+    pub fn is_degenerate(&self) -> bool {
+        self.rx.abs() <= f32::EPSILON || self.ry.abs() <= f32::EPSILON || self.from == self.to
+    }
+
+    /// Converts to center parameterization, scaling up radii that are too small.
+    pub fn center(&self) -> ArcCenter {
+        let phi = self.angle.to_radians();
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let (mut rx, mut ry) = (self.rx.abs(), self.ry.abs());
+        let (dx, dy) = (
+            (self.from.0 - self.to.0) / 2.,
+            (self.from.1 - self.to.1) / 2.,
+        );
+        let x1 = cos_phi * dx + sin_phi * dy;
+        let y1 = -sin_phi * dx + cos_phi * dy;
+
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1. {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coef = if den == 0. {
+            0.
+        } else {
+            (num / den).max(0.).sqrt()
+        };
+        if self.large_arc == self.sweep {
+            coef = -coef;
+        }
+        let cx1 = coef * rx * y1 / ry;
+        let cy1 = -coef * ry * x1 / rx;
+        let center = (
+            cos_phi * cx1 - sin_phi * cy1 + (self.from.0 + self.to.0) / 2.,
+            sin_phi * cx1 + cos_phi * cy1 + (self.from.1 + self.to.1) / 2.,
+        );
+
+        let u = ((x1 - cx1) / rx, (y1 - cy1) / ry);
+        let v = ((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+        let start_angle = u.1.atan2(u.0);
+        let mut sweep_angle = cross(u, v).atan2(dot(u, v));
+        if !self.sweep && sweep_angle > 0. {
+            sweep_angle -= 2. * PI;
+        } else if self.sweep && sweep_angle < 0. {
+            sweep_angle += 2. * PI;
+        }
+
+        ArcCenter {
+            center,
+            rx,
+            ry,
+            phi,
+            start_angle,
+            sweep_angle,
+        }
+    }
+
+    /// Approximates the arc with cubic Bézier segments spanning at most 90° each.
+    pub fn to_cubics(&self) -> Vec<Segment> {
+        if self.is_degenerate() {
+            return vec![Segment::Line(self.from, self.to)];
+        }
+        let c = self.center();
+        let count = (c.sweep_angle.abs() / (PI / 2.)).ceil().max(1.) as usize;
+        let step = c.sweep_angle / count as f32;
+        let k = 4. / 3. * (step / 4.).tan();
+        let mut result = Vec::with_capacity(count);
+        let mut from = self.from;
+        for i in 0..count {
+            let a0 = c.start_angle + step * i as f32;
+            let a1 = a0 + step;
+            let to = if i + 1 == count {
+                self.to
+            } else {
+                c.point_at_angle(a1)
+            };
+            let c1 = add(from, scale(c.derivative_at_angle(a0), k));
+            let c2 = sub(to, scale(c.derivative_at_angle(a1), k));
+            result.push(Segment::Cubic(from, c1, c2, to));
+            from = to;
+        }
+        result
+    }
+}
+
+/// A single drawing segment with absolute coordinates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Segment {
+    Line((f32, f32), (f32, f32)),
+    Quadratic((f32, f32), (f32, f32), (f32, f32)),
+    Cubic((f32, f32), (f32, f32), (f32, f32), (f32, f32)),
+    Arc(Arc),
+}
+
+impl Segment {
+    pub fn start(&self) -> (f32, f32) {
+        match self {
+            Segment::Line(p, _) | Segment::Quadratic(p, _, _) | Segment::Cubic(p, _, _, _) => *p,
+            Segment::Arc(a) => a.from,
+        }
+    }
+
+    pub fn end(&self) -> (f32, f32) {
+        match self {
+            Segment::Line(_, p) | Segment::Quadratic(_, _, p) | Segment::Cubic(_, _, _, p) => *p,
+            Segment::Arc(a) => a.to,
+        }
+    }
+
+    pub fn eval(&self, t: f32) -> (f32, f32) {
+        match *self {
+            Segment::Line(p0, p1) => lerp(p0, p1, t),
+            Segment::Quadratic(p0, c, p1) => lerp(lerp(p0, c, t), lerp(c, p1, t), t),
+            Segment::Cubic(p0, c1, c2, p1) => {
+                let (a, b, c) = (lerp(p0, c1, t), lerp(c1, c2, t), lerp(c2, p1, t));
+                lerp(lerp(a, b, t), lerp(b, c, t), t)
+            }
+            Segment::Arc(a) => {
+                if a.is_degenerate() {
+                    return lerp(a.from, a.to, t);
+                }
+                let c = a.center();
+                c.point_at_angle(c.start_angle + c.sweep_angle * t)
+            }
+        }
+    }
+
+    pub fn derivative(&self, t: f32) -> (f32, f32) {
+        match *self {
+            Segment::Line(p0, p1) => sub(p1, p0),
+            Segment::Quadratic(p0, c, p1) => scale(lerp(sub(c, p0), sub(p1, c), t), 2.),
+            Segment::Cubic(p0, c1, c2, p1) => {
+                let (a, b, c) = (sub(c1, p0), sub(c2, c1), sub(p1, c2));
+                scale(lerp(lerp(a, b, t), lerp(b, c, t), t), 3.)
+            }
+            Segment::Arc(a) => {
+                if a.is_degenerate() {
+                    return sub(a.to, a.from);
+                }
+                let c = a.center();
+                scale(
+                    c.derivative_at_angle(c.start_angle + c.sweep_angle * t),
+                    c.sweep_angle,
+                )
+            }
+        }
+    }
+
+    /// Splits a Bézier or line segment at `t`. Arcs are split on their angle.
+    pub fn split(&self, t: f32) -> (Segment, Segment) {
+        match *self {
+            Segment::Line(p0, p1) => {
+                let m = lerp(p0, p1, t);
+                (Segment::Line(p0, m), Segment::Line(m, p1))
+            }
+            Segment::Quadratic(p0, c, p1) => {
+                let (a, b) = (lerp(p0, c, t), lerp(c, p1, t));
+                let m = lerp(a, b, t);
+                (Segment::Quadratic(p0, a, m), Segment::Quadratic(m, b, p1))
+            }
+            Segment::Cubic(p0, c1, c2, p1) => {
+                let (a, b, c) = (lerp(p0, c1, t), lerp(c1, c2, t), lerp(c2, p1, t));
+                let (d, e) = (lerp(a, b, t), lerp(b, c, t));
+                let m = lerp(d, e, t);
+                (Segment::Cubic(p0, a, d, m), Segment::Cubic(m, e, c, p1))
+            }
+            Segment::Arc(a) => {
+                if a.is_degenerate() {
+                    return Segment::Line(a.from, a.to).split(t);
+                }
+                let m = self.eval(t);
+                let c = a.center();
+                let first_large = (c.sweep_angle * t).abs() > PI;
+                let second_large = (c.sweep_angle * (1. - t)).abs() > PI;
+                (
+                    Segment::Arc(Arc {
+                        to: m,
+                        rx: c.rx,
+                        ry: c.ry,
+                        large_arc: first_large,
+                        ..a
+                    }),
+                    Segment::Arc(Arc {
+                        from: m,
+                        rx: c.rx,
+                        ry: c.ry,
+                        large_arc: second_large,
+                        ..a
+                    }),
+                )
+            }
+        }
+    }
+
+    /// Returns the segment as one or more cubic Bézier curves.
+    pub fn to_cubics(&self) -> Vec<Segment> {
+        match *self {
+            Segment::Line(p0, p1) => vec![Segment::Cubic(
+                p0,
+                lerp(p0, p1, 1. / 3.),
+                lerp(p0, p1, 2. / 3.),
+                p1,
+            )],
+            Segment::Quadratic(p0, c, p1) => vec![Segment::Cubic(
+                p0,
+                lerp(p0, c, 2. / 3.),
+                lerp(p1, c, 2. / 3.),
+                p1,
+            )],
+            Segment::Cubic(..) => vec![*self],
+            Segment::Arc(a) => a.to_cubics(),
+        }
+    }
+
+    /// Reverses the drawing direction of the segment.
+    pub fn reversed(&self) -> Segment {
+        match *self {
+            Segment::Line(p0, p1) => Segment::Line(p1, p0),
+            Segment::Quadratic(p0, c, p1) => Segment::Quadratic(p1, c, p0),
+            Segment::Cubic(p0, c1, c2, p1) => Segment::Cubic(p1, c2, c1, p0),
+            Segment::Arc(a) => Segment::Arc(Arc {
+                from: a.to,
+                to: a.from,
+                sweep: !a.sweep,
+                ..a
+            }),
+        }
+    }
+
+    /// Appends points approximating the segment to `out`, excluding the start point.
+    pub fn flatten_into(&self, tolerance: f32, out: &mut Vec<(f32, f32)>) {
+        let count = match *self {
+            Segment::Line(_, p1) => {
+                out.push(p1);
+                return;
+            }
+            Segment::Quadratic(p0, c, p1) => {
+                let dd = length(add(sub(p0, scale(c, 2.)), p1));
+                (0.25 * dd / tolerance).sqrt().ceil()
+            }
+            Segment::Cubic(p0, c1, c2, p1) => {
+                let dd = length(add(sub(p0, scale(c1, 2.)), c2))
+                    .max(length(add(sub(c1, scale(c2, 2.)), p1)));
+                (0.75 * dd / tolerance).sqrt().ceil()
+            }
+            Segment::Arc(a) => {
+                for cubic in a.to_cubics() {
+                    cubic.flatten_into(tolerance, out);
+                }
+                return;
+            }
+        };
+        let count = (count as usize).clamp(1, 1000);
+        for i in 1..=count {
+            out.push(self.eval(i as f32 / count as f32));
+        }
+    }
+
+    /// Bounding box of the control polygon, which always contains the segment.
+    pub fn control_bounds(&self) -> ((f32, f32), (f32, f32)) {
+        let points = match *self {
+            Segment::Line(p0, p1) => vec![p0, p1],
+            Segment::Quadratic(p0, c, p1) => vec![p0, c, p1],
+            Segment::Cubic(p0, c1, c2, p1) => vec![p0, c1, c2, p1],
+            Segment::Arc(a) => a
+                .to_cubics()
+                .iter()
+                .flat_map(|c| match *c {
+                    Segment::Cubic(p0, c1, c2, p1) => vec![p0, c1, c2, p1],
+                    Segment::Line(p0, p1) => vec![p0, p1],
+                    _ => unreachable!(),
+                })
+                .collect(),
+        };
+        bounds_of(&points).unwrap()
+    }
+}
+
+pub(crate) fn bounds_of(points: &[(f32, f32)]) -> Option<((f32, f32), (f32, f32))> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(min, max), p| {
+        (
+            (min.0.min(p.0), min.1.min(p.1)),
+            (max.0.max(p.0), max.1.max(p.1)),
+        )
+    }))
+}
+
+/// A connected run of segments started by a move-to command.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Subpath {
+    pub start: (f32, f32),
+    pub segments: Vec<Segment>,
+    /// `true` if the subpath was closed with a close-path command. The closing
+    /// line is part of `segments` unless start and end already coincide.
+    pub closed: bool,
+}
+
+impl Subpath {
+    pub fn end(&self) -> (f32, f32) {
+        self.segments.last().map(|s| s.end()).unwrap_or(self.start)
+    }
+
+    /// Approximates the subpath with a polyline, starting at the start point.
+    pub fn flatten(&self, tolerance: f32) -> Vec<(f32, f32)> {
+        let mut points = vec![self.start];
+        for segment in &self.segments {
+            segment.flatten_into(tolerance, &mut points);
+        }
+        points
+    }
+}
+
+impl PathShape {
+    /// Resolves relative, shorthand and smooth commands into absolute segments,
+    /// grouped by subpath.
+    pub fn subpaths(&self) -> Vec<Subpath> {
+        let mut result: Vec<Subpath> = vec![];
+        let mut current: Option<Subpath> = None;
+        let mut pos = (0f32, 0f32);
+        let mut start = (0f32, 0f32);
+        let mut last_control: Option<(char, (f32, f32))> = None;
+
+        for command in &self.elements {
+            let base = if command.relative { pos } else { (0., 0.) };
+            let target = add(base, command.movement);
+            if command.command == PathCommandKind::MoveTo {
+                if let Some(subpath) = current.take() {
+                    result.push(subpath);
+                }
+                pos = target;
+                start = target;
+                current = Some(Subpath {
+                    start,
+                    ..Default::default()
+                });
+                last_control = None;
+                continue;
+            }
+            let subpath = current.get_or_insert_with(|| Subpath {
+                start,
+                ..Default::default()
+            });
+            let reflect = |kind: char| match last_control {
+                Some((k, c)) if k == kind => sub(scale(pos, 2.), c),
+                _ => pos,
+            };
+            let (segment, control) = match command.command {
+                PathCommandKind::MoveTo => unreachable!(),
+                PathCommandKind::LineTo => (Segment::Line(pos, target), None),
+                PathCommandKind::HorizontalLineTo => {
+                    let to = (base.0 + command.movement.0, pos.1);
+                    (Segment::Line(pos, to), None)
+                }
+                PathCommandKind::VerticalLineTo => {
+                    let to = (pos.0, base.1 + command.movement.1);
+                    (Segment::Line(pos, to), None)
+                }
+                PathCommandKind::CubicBezierCurve(c1, c2) => {
+                    let c2 = add(base, c2);
+                    (
+                        Segment::Cubic(pos, add(base, c1), c2, target),
+                        Some(('C', c2)),
+                    )
+                }
+                PathCommandKind::CubicBezierCurveSmooth(c2) => {
+                    let c2 = add(base, c2);
+                    (
+                        Segment::Cubic(pos, reflect('C'), c2, target),
+                        Some(('C', c2)),
+                    )
+                }
+                PathCommandKind::QuadraticBezierCurve(c) => {
+                    let c = add(base, c);
+                    (Segment::Quadratic(pos, c, target), Some(('Q', c)))
+                }
+                PathCommandKind::QuadraticBezierCurveSmooth => {
+                    let c = reflect('Q');
+                    (Segment::Quadratic(pos, c, target), Some(('Q', c)))
+                }
+                PathCommandKind::EllipticalArcCurve(rx, ry, angle, large_arc, sweep) => (
+                    Segment::Arc(Arc {
+                        from: pos,
+                        to: target,
+                        rx,
+                        ry,
+                        angle,
+                        large_arc,
+                        sweep,
+                    }),
+                    None,
+                ),
+                PathCommandKind::ClosePath => {
+                    if pos != subpath.start {
+                        subpath.segments.push(Segment::Line(pos, subpath.start));
+                    }
+                    subpath.closed = true;
+                    pos = subpath.start;
+                    result.push(current.take().unwrap());
+                    last_control = None;
+                    continue;
+                }
+            };
+            pos = segment.end();
+            subpath.segments.push(segment);
+            last_control = control;
+        }
+        if let Some(subpath) = current {
+            result.push(subpath);
+        }
+        result
+    }
+
+    /// Builds a path from absolute subpaths, emitting one command per segment.
+    pub fn from_subpaths(subpaths: &[Subpath]) -> Self {
+        let mut elements = vec![];
+        for subpath in subpaths {
+            elements.push(PathCommand::move_to(subpath.start));
+            let mut segments = subpath.segments.as_slice();
+            if subpath.closed {
+                if let Some(Segment::Line(_, to)) = segments.last() {
+                    if *to == subpath.start {
+                        segments = &segments[..segments.len() - 1];
+                    }
+                }
+            }
+            for segment in segments {
+                elements.push(match *segment {
+                    Segment::Line(_, p1) => PathCommand::line_to(p1),
+                    Segment::Quadratic(_, c, p1) => PathCommand::quadratic_bezier_curve(p1, c),
+                    Segment::Cubic(_, c1, c2, p1) => PathCommand::cubic_bezier_curve(p1, c1, c2),
+                    Segment::Arc(a) => PathCommand::elliptical_arc_curve(
+                        a.to,
+                        a.rx,
+                        a.ry,
+                        a.angle,
+                        a.large_arc,
+                        a.sweep,
+                    ),
+                });
+            }
+            if subpath.closed {
+                elements.push(PathCommand::close());
+            }
+        }
+        Self { elements }
+    }
+
+    /// Bounding box of all control points, or `None` for an empty path.
+    pub fn control_bounds(&self) -> Option<((f32, f32), (f32, f32))> {
+        let mut points = vec![];
+        for subpath in self.subpaths() {
+            points.push(subpath.start);
+            for segment in subpath.segments {
+                let (min, max) = segment.control_bounds();
+                points.push(min);
+                points.push(max);
+            }
+        }
+        bounds_of(&points)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::elements::path::{PathCommand, PathShape};
+
+/// Ellipse drawn clockwise as two half arcs, starting at its rightmost point.
+/// Empty if either radius is not positive.
+pub(crate) fn ellipse(center: (f32, f32), radius: (f32, f32)) -> PathShape {
+    let ((cx, cy), (rx, ry)) = (center, radius);
+    if rx <= 0. || ry <= 0. {
+        return PathShape::new();
+    }
+    PathShape::new().with_commands(vec![
+        PathCommand::move_to((cx + rx, cy)),
+        PathCommand::elliptical_arc_curve((cx - rx, cy), rx, ry, 0., false, true),
+        PathCommand::elliptical_arc_curve((cx + rx, cy), rx, ry, 0., false, true),
+        PathCommand::close(),
+    ])
+}
+
+/// Rectangle with corners rounded by `radius`, following the decomposition
+/// of the `rect` element. Radii are clamped to half the size; the rectangle
+/// is empty if its width or height is not positive.
+pub(crate) fn rounded_rect(
+    position: (f32, f32),
+    size: (f32, f32),
+    radius: (f32, f32),
+) -> PathShape {
+    let ((x, y), (w, h)) = (position, size);
+    if w <= 0. || h <= 0. {
+        return PathShape::new();
+    }
+    let rx = radius.0.clamp(0., w / 2.);
+    let ry = radius.1.clamp(0., h / 2.);
+    if rx == 0. || ry == 0. {
+        return PathShape::new().with_commands(vec![
+            PathCommand::move_to((x, y)),
+            PathCommand::horizontal_line_to(x + w),
+            PathCommand::vertical_line_to(y + h),
+            PathCommand::horizontal_line_to(x),
+            PathCommand::close(),
+        ]);
+    }
+    let corner = |to| PathCommand::elliptical_arc_curve(to, rx, ry, 0., false, true);
+    PathShape::new().with_commands(vec![
+        PathCommand::move_to((x + rx, y)),
+        PathCommand::horizontal_line_to(x + w - rx),
+        corner((x + w, y + ry)),
+        PathCommand::vertical_line_to(y + h - ry),
+        corner((x + w - rx, y + h)),
+        PathCommand::horizontal_line_to(x + rx),
+        corner((x, y + h - ry)),
+        PathCommand::vertical_line_to(y + ry),
+        corner((x + rx, y)),
+        PathCommand::close(),
+    ])
+}
+
+/// Value of the `points` attribute of `polyline` and `polygon`.
+pub(crate) fn points_attribute(points: &[(f32, f32)]) -> String {
+    points
+        .iter()
+        .map(|(x, y)| format!("{},{}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Straight lines through `points`, closed back to the first one if `closed`.
+pub(crate) fn polyline(points: &[(f32, f32)], closed: bool) -> PathShape {
+    let Some((&first, rest)) = points.split_first() else {
+        return PathShape::new();
+    };
+    let mut commands = vec![PathCommand::move_to(first)];
+    commands.extend(rest.iter().map(|&p| PathCommand::line_to(p)));
+    if closed {
+        commands.push(PathCommand::close());
+    }
+    PathShape::new().with_commands(commands)
+}
+
+/// Closed polygon through `points` whose corners are replaced by arcs of
+/// `radius` tangent to both edges. A corner's radius shrinks so that its arc
+/// takes at most half of either edge.
+pub(crate) fn rounded_polygon(points: &[(f32, f32)], radius: f32) -> PathShape {
+    let n = points.len();
+    if radius <= 0. || n < 3 {
+        return polyline(points, true);
+    }
+    let mut commands = vec![];
+    for (i, &corner) in points.iter().enumerate() {
+        let (prev, next) = (points[(i + n - 1) % n], points[(i + 1) % n]);
+        let a = (prev.0 - corner.0, prev.1 - corner.1);
+        let b = (next.0 - corner.0, next.1 - corner.1);
+        let (la, lb) = (a.0.hypot(a.1), b.0.hypot(b.1));
+        // Half of the angle between the edges gives the tangent distance.
+        let tan = match la > 0. && lb > 0. {
+            true => (((a.0 * b.0 + a.1 * b.1) / (la * lb)).clamp(-1., 1.).acos() / 2.).tan(),
+            false => 0.,
+        };
+        let distance = match tan > 0. {
+            true => (radius / tan).min(la / 2.).min(lb / 2.),
+            false => 0.,
+        };
+        let start = match distance > 0. {
+            true => (
+                corner.0 + a.0 / la * distance,
+                corner.1 + a.1 / la * distance,
+            ),
+            false => corner,
+        };
+        commands.push(match i {
+            0 => PathCommand::move_to(start),
+            _ => PathCommand::line_to(start),
+        });
+        if distance > 0. {
+            let end = (
+                corner.0 + b.0 / lb * distance,
+                corner.1 + b.1 / lb * distance,
+            );
+            let r = distance * tan;
+            // Turning clockwise on screen sweeps in the positive direction.
+            let sweep = a.0 * b.1 - a.1 * b.0 < 0.;
+            commands.push(PathCommand::elliptical_arc_curve(
+                end, r, r, 0., false, sweep,
+            ));
+        }
+    }
+    commands.push(PathCommand::close());
+    PathShape::new().with_commands(commands)
+}
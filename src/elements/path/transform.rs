@@ -0,0 +1,199 @@
+use crate::elements::path::{PathCommand, PathCommandKind, PathShape};
+use crate::elements::value::transform::Transform;
+
+/// Returns `true` if `x` vanishes next to `other`, so that an axis aligned
+/// line can stay axis aligned.
+fn negligible(x: f32, other: f32) -> bool {
+    x.abs() <= 1e-6 * other.abs().max(x.abs()).max(f32::MIN_POSITIVE)
+}
+
+/// Radii and x-axis rotation (in degrees) of the ellipse with radii `rx`, `ry`
+/// rotated by `angle` degrees after being mapped through `matrix`.
+fn transform_ellipse(matrix: &Transform, rx: f32, ry: f32, angle: f32) -> (f32, f32, f32) {
+    let (sin, cos) = (angle as f64).to_radians().sin_cos();
+    let (rx, ry) = (rx.abs() as f64, ry.abs() as f64);
+    let (a, b, c, d) = (
+        matrix.a as f64,
+        matrix.b as f64,
+        matrix.c as f64,
+        matrix.d as f64,
+    );
+    // Columns are the images of the two semi-axes.
+    let (p, r) = (rx * (a * cos + c * sin), rx * (b * cos + d * sin));
+    let (q, s) = (ry * (c * cos - a * sin), ry * (d * cos - b * sin));
+    // Eigen decomposition of L * L^T gives the squared radii and their axes.
+    let (e_a, e_b, e_c) = (p * p + q * q, p * r + q * s, r * r + s * s);
+    let mean = (e_a + e_c) / 2.;
+    let radius = (((e_a - e_c) / 2.).powi(2) + e_b * e_b).sqrt();
+    let major = (mean + radius).max(0.).sqrt();
+    let minor = (mean - radius).max(0.).sqrt();
+    let rotation = if radius < 1e-12 * mean.max(f64::MIN_POSITIVE) {
+        0.
+    } else {
+        0.5 * (2. * e_b).atan2(e_a - e_c)
+    };
+    (
+        major as f32,
+        minor as f32,
+        rotation.to_degrees().rem_euclid(180.) as f32,
+    )
+}
+
+impl PathShape {
+    /// Bakes `matrix` into the coordinates of every command. Horizontal and
+    /// vertical lines become general lines unless they stay axis aligned,
+    /// relative commands stay relative and arcs are refitted to the
+    /// transformed ellipse. A leading relative command becomes absolute, as
+    /// its reference point is the untransformed origin.
+    pub fn transform(&self, matrix: &Transform) -> PathShape {
+        let map = |p: (f32, f32), relative: bool| {
+            if relative {
+                matrix.apply_vector(p)
+            } else {
+                matrix.apply(p)
+            }
+        };
+        let reflects = matrix.determinant() < 0.;
+        let mut pos = (0f32, 0f32);
+        let mut start = (0f32, 0f32);
+        let mut elements = vec![];
+        for command in &self.elements {
+            let relative = command.relative;
+            let target = match command.command {
+                PathCommandKind::HorizontalLineTo if relative => {
+                    (pos.0 + command.movement.0, pos.1)
+                }
+                PathCommandKind::HorizontalLineTo => (command.movement.0, pos.1),
+                PathCommandKind::VerticalLineTo if relative => (pos.0, pos.1 + command.movement.1),
+                PathCommandKind::VerticalLineTo => (pos.0, command.movement.1),
+                PathCommandKind::ClosePath => start,
+                _ if relative => (pos.0 + command.movement.0, pos.1 + command.movement.1),
+                _ => command.movement,
+            };
+            let relative = relative && !elements.is_empty();
+            let movement = map(command.movement, relative);
+            let transformed = match command.command {
+                PathCommandKind::MoveTo | PathCommandKind::LineTo => PathCommand {
+                    relative,
+                    movement,
+                    ..command.clone()
+                },
+                PathCommandKind::HorizontalLineTo | PathCommandKind::VerticalLineTo => {
+                    let (delta, end) = (
+                        matrix.apply_vector((target.0 - pos.0, target.1 - pos.1)),
+                        matrix.apply(target),
+                    );
+                    let line = if negligible(delta.1, delta.0) {
+                        PathCommand::horizontal_line_to(if relative { delta.0 } else { end.0 })
+                    } else if negligible(delta.0, delta.1) {
+                        PathCommand::vertical_line_to(if relative { delta.1 } else { end.1 })
+                    } else {
+                        PathCommand::line_to(if relative { delta } else { end })
+                    };
+                    PathCommand { relative, ..line }
+                }
+                PathCommandKind::CubicBezierCurve(c1, c2) => PathCommand {
+                    relative,
+                    movement,
+                    command: PathCommandKind::CubicBezierCurve(
+                        map(c1, relative),
+                        map(c2, relative),
+                    ),
+                },
+                PathCommandKind::CubicBezierCurveSmooth(c2) => PathCommand {
+                    relative,
+                    movement,
+                    command: PathCommandKind::CubicBezierCurveSmooth(map(c2, relative)),
+                },
+                PathCommandKind::QuadraticBezierCurve(c) => PathCommand {
+                    relative,
+                    movement,
+                    command: PathCommandKind::QuadraticBezierCurve(map(c, relative)),
+                },
+                PathCommandKind::QuadraticBezierCurveSmooth => PathCommand {
+                    relative,
+                    movement,
+                    command: PathCommandKind::QuadraticBezierCurveSmooth,
+                },
+                PathCommandKind::EllipticalArcCurve(rx, ry, angle, large_arc, sweep) => {
+                    let (rx, ry, angle) = transform_ellipse(matrix, rx, ry, angle);
+                    PathCommand {
+                        relative,
+                        movement,
+                        command: PathCommandKind::EllipticalArcCurve(
+                            rx,
+                            ry,
+                            angle,
+                            large_arc,
+                            sweep != reflects,
+                        ),
+                    }
+                }
+                PathCommandKind::ClosePath => command.clone(),
+            };
+            if command.command == PathCommandKind::MoveTo {
+                start = target;
+            }
+            pos = target;
+            elements.push(transformed);
+        }
+        PathShape { elements }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_lines() {
+        let shape = PathShape::parse_from_str("M 0,0 H 10 v 10 Z").unwrap();
+        let rotated = shape.transform(&Transform::rotate(90.));
+        let segments = &rotated.subpaths()[0].segments;
+        let close =
+            |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4;
+        assert!(close(segments[0].end(), (0., 10.)));
+        assert!(close(segments[1].end(), (-10., 10.)));
+        assert_eq!(rotated.elements[1].command, PathCommandKind::VerticalLineTo);
+        assert_eq!(
+            rotated.elements[2].command,
+            PathCommandKind::HorizontalLineTo
+        );
+        assert!(rotated.elements[2].relative);
+
+        let skewed = shape.transform(&Transform::skew_x(45.));
+        assert_eq!(
+            skewed.elements[1].command,
+            PathCommandKind::HorizontalLineTo
+        );
+        assert_eq!(skewed.elements[2].command, PathCommandKind::LineTo);
+    }
+
+    #[test]
+    fn test_transform_leading_relative_move() {
+        let shape = PathShape::parse_from_str("m 10,10 l 5,0 z m 1,1 l 0,5").unwrap();
+        let translated = shape.transform(&Transform::translate(5., 5.));
+        assert!(!translated.elements[0].relative);
+        assert_eq!(translated.elements[0].movement, (15., 15.));
+        assert!(translated.elements[3].relative);
+        assert_eq!(translated.subpaths()[1].segments[0].end(), (16., 21.));
+    }
+
+    #[test]
+    fn test_transform_arcs() {
+        let circle = PathShape::parse_from_str("M 0,5 A 5 5 0 0 1 10,5 A 5 5 0 0 1 0,5 Z").unwrap();
+        let matrix = Transform::scale(2., 1.)
+            .then(&Transform::skew_x(30.))
+            .then(&Transform::scale(-1., 1.));
+        let transformed = circle.transform(&matrix);
+        for subpath in circle.subpaths() {
+            for segment in subpath.segments {
+                for i in 0..=8 {
+                    let p = matrix.apply(segment.eval(i as f32 / 8.));
+                    assert!(transformed.stroke_contains(p, 0.2));
+                }
+            }
+        }
+        assert!(transformed.signed_area() * circle.signed_area() < 0.);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::elements::path::hit::{fill_rule_matches, polyline_distance, polyline_winding};
+use crate::elements::path::segment::{bounds_of, DEFAULT_TOLERANCE};
+use crate::elements::value::parse::parse_number;
+use crate::elements::value::transform::Transform;
+use crate::elements::value::FillRule;
+use crate::SvgElement;
+use std::collections::HashMap;
+
+/// Presentation state inherited down the element tree.
+#[derive(Copy, Clone, Debug)]
+struct PaintState {
+    transform: Transform,
+    filled: bool,
+    fill_rule: FillRule,
+    stroked: bool,
+    stroke_width: f32,
+}
+
+impl Default for PaintState {
+    fn default() -> Self {
+        Self {
+            transform: Transform::IDENTITY,
+            filled: true,
+            fill_rule: FillRule::NonZero,
+            stroked: false,
+            stroke_width: 1.,
+        }
+    }
+}
+
+impl PaintState {
+    fn apply(mut self, attributes: &HashMap<String, String>) -> Self {
+        if let Some(transform) = attributes.get("transform").and_then(|t| t.parse().ok()) {
+            self.transform = self.transform * transform;
+        }
+        if let Some(fill) = attributes.get("fill") {
+            self.filled = fill.trim() != "none";
+        }
+        if let Some(fill_rule) = attributes.get("fill-rule").and_then(|f| f.parse().ok()) {
+            self.fill_rule = fill_rule;
+        }
+        if let Some(stroke) = attributes.get("stroke") {
+            self.stroked = stroke.trim() != "none";
+        }
+        if let Some(width) = attributes.get("stroke-width").and_then(|w| parse_number(w)) {
+            self.stroke_width = width;
+        }
+        self
+    }
+
+    fn stroke_width(&self) -> Option<f32> {
+        self.stroked.then_some(self.stroke_width)
+    }
+}
+
+/// A pickable element, identified by the child indices leading to it from the root.
+#[derive(Clone, Debug)]
+pub struct PickEntry {
+    pub location: Vec<usize>,
+    pub name: String,
+    polylines: Vec<Vec<(f32, f32)>>,
+    inverse: Transform,
+    filled: bool,
+    fill_rule: FillRule,
+    stroke_width: Option<f32>,
+    bounds: ((f32, f32), (f32, f32)),
+}
+
+impl PickEntry {
+    /// Bounding box of the element in root coordinates.
+    pub fn bounds(&self) -> ((f32, f32), (f32, f32)) {
+        self.bounds
+    }
+
+    /// Exact hit test of a point given in root coordinates.
+    pub fn hit(&self, point: (f32, f32)) -> bool {
+        let local = self.inverse.apply(point);
+        (self.filled && fill_rule_matches(self.fill_rule, polyline_winding(&self.polylines, local)))
+            || self
+                .stroke_width
+                .is_some_and(|w| polyline_distance(&self.polylines, local) <= w / 2.)
+    }
+}
+
+/// Uniform grid over the document bounds, storing entry indices per cell.
+#[derive(Clone, Debug)]
+struct Grid {
+    origin: (f32, f32),
+    cell_size: (f32, f32),
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    fn new(entries: &[PickEntry]) -> Self {
+        let bounds = bounds_of(
+            &entries
+                .iter()
+                .flat_map(|e| [e.bounds.0, e.bounds.1])
+                .collect::<Vec<_>>(),
+        )
+        .unwrap_or(((0., 0.), (0., 0.)));
+        let side = ((entries.len() as f32).sqrt().ceil() as usize).clamp(1, 512);
+        let cell_size = (
+            ((bounds.1 .0 - bounds.0 .0) / side as f32).max(f32::EPSILON),
+            ((bounds.1 .1 - bounds.0 .1) / side as f32).max(f32::EPSILON),
+        );
+        let mut grid = Self {
+            origin: bounds.0,
+            cell_size,
+            columns: side,
+            rows: side,
+            cells: vec![vec![]; side * side],
+        };
+        for (index, entry) in entries.iter().enumerate() {
+            let (x0, y0) = grid.cell(entry.bounds.0);
+            let (x1, y1) = grid.cell(entry.bounds.1);
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    grid.cells[y * grid.columns + x].push(index);
+                }
+            }
+        }
+        grid
+    }
+
+    fn cell(&self, point: (f32, f32)) -> (usize, usize) {
+        let x = ((point.0 - self.origin.0) / self.cell_size.0).floor();
+        let y = ((point.1 - self.origin.1) / self.cell_size.1).floor();
+        (
+            (x.max(0.) as usize).min(self.columns - 1),
+            (y.max(0.) as usize).min(self.rows - 1),
+        )
+    }
+
+    fn candidates(&self, point: (f32, f32)) -> &[usize] {
+        let (x, y) = self.cell(point);
+        &self.cells[y * self.columns + x]
+    }
+}
+
+/// Spatial index answering "which element is under this point" for a whole document.
+#[derive(Clone, Debug)]
+pub struct PickIndex {
+    entries: Vec<PickEntry>,
+    grid: Grid,
+}
+
+impl PickIndex {
+    /// Indexes every element below `root` that has path geometry.
+    pub fn new(root: &dyn SvgElement) -> Self {
+        let mut entries = vec![];
+        Self::collect(root, PaintState::default(), &mut vec![], &mut entries);
+        let grid = Grid::new(&entries);
+        Self { entries, grid }
+    }
+
+    fn collect(
+        element: &dyn SvgElement,
+        state: PaintState,
+        location: &mut Vec<usize>,
+        entries: &mut Vec<PickEntry>,
+    ) {
+        let state = state.apply(element.attributes());
+        if let (Some(shape), Some(inverse)) = (element.path_shape(), state.transform.inverse()) {
+            let polylines = shape.flatten(DEFAULT_TOLERANCE);
+            let half_stroke = state.stroke_width().unwrap_or(0.) / 2.;
+            let corners = polylines
+                .iter()
+                .flatten()
+                .flat_map(|&(x, y)| {
+                    [
+                        (x - half_stroke, y - half_stroke),
+                        (x + half_stroke, y + half_stroke),
+                        (x - half_stroke, y + half_stroke),
+                        (x + half_stroke, y - half_stroke),
+                    ]
+                })
+                .map(|p| state.transform.apply(p))
+                .collect::<Vec<_>>();
+            if let Some(bounds) = bounds_of(&corners) {
+                entries.push(PickEntry {
+                    location: location.clone(),
+                    name: element.name().to_string(),
+                    polylines,
+                    inverse,
+                    filled: state.filled,
+                    fill_rule: state.fill_rule,
+                    stroke_width: state.stroke_width(),
+                    bounds,
+                });
+            }
+        }
+        for (i, child) in element.children().iter().enumerate() {
+            if let Some(child) = child.as_element() {
+                location.push(i);
+                Self::collect(child, state, location, entries);
+                location.pop();
+            }
+        }
+    }
+
+    pub fn entries(&self) -> &[PickEntry] {
+        &self.entries
+    }
+
+    /// Returns the topmost element (last in document order) under `point`.
+    pub fn pick(&self, point: (f32, f32)) -> Option<&PickEntry> {
+        self.grid
+            .candidates(point)
+            .iter()
+            .rev()
+            .map(|&i| &self.entries[i])
+            .find(|e| {
+                let (min, max) = e.bounds;
+                point.0 >= min.0
+                    && point.0 <= max.0
+                    && point.1 >= min.1
+                    && point.1 <= max.1
+                    && e.hit(point)
+            })
+    }
+
+    /// Returns every element under `point`, topmost first.
+    pub fn pick_all(&self, point: (f32, f32)) -> Vec<&PickEntry> {
+        self.grid
+            .candidates(point)
+            .iter()
+            .rev()
+            .map(|&i| &self.entries[i])
+            .filter(|e| e.hit(point))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::group::Group;
+    use crate::elements::path::PathShape;
+
+    #[test]
+    fn test_pick_topmost() {
+        let square = |d: &str| PathShape::parse_from_str(d).unwrap().to_path();
+        let mut group = Group::new()
+            .with_child(square("M 0,0 H 10 V 10 H 0 Z"))
+            .with_child(square("M 5,5 H 15 V 15 H 5 Z"));
+        group.with_attribute("transform".to_string(), "translate(100 0)".to_string());
+        let index = PickIndex::new(&group);
+        assert_eq!(index.pick((107., 7.)).unwrap().location, vec![1]);
+        assert_eq!(index.pick((102., 2.)).unwrap().location, vec![0]);
+        assert_eq!(index.pick_all((107., 7.)).len(), 2);
+        assert!(index.pick((7., 7.)).is_none());
+    }
+
+    #[test]
+    fn test_inherited_stroke_width() {
+        let mut line = PathShape::parse_from_str("M 0,0 H 10").unwrap().to_path();
+        line.with_attribute("stroke".to_string(), "red".to_string());
+        let mut group = Group::new().with_child(line);
+        group.with_attribute("stroke-width".to_string(), "5px".to_string());
+        let index = PickIndex::new(&group);
+        assert!(index.pick((5., 2.)).is_some());
+        assert!(index.pick((5., 3.)).is_none());
+    }
+}
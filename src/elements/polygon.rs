@@ -0,0 +1,56 @@
+use crate::elements::path::{shapes, PathShape};
+use crate::SvgElement;
+use std::collections::HashMap;
+
+pub struct Polygon {
+    points: Vec<(f32, f32)>,
+    attributes: HashMap<String, String>,
+    value: Option<String>,
+}
+
+impl Polygon {
+    pub fn new(points: Vec<(f32, f32)>) -> Self {
+        Self {
+            points,
+            attributes: HashMap::new(),
+            value: None,
+        }
+    }
+
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+
+    pub fn to_path_shape(&self) -> PathShape {
+        shapes::polyline(&self.points, true)
+    }
+}
+
+impl SvgElement for Polygon {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        "polygon"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![(
+            "points".to_string(),
+            Some(shapes::points_attribute(&self.points)),
+        )]
+    }
+
+    fn value(&self) -> Option<&String> {
+        self.value.as_ref()
+    }
+
+    fn path_shape(&self) -> Option<PathShape> {
+        Some(self.to_path_shape())
+    }
+}
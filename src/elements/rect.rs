@@ -0,0 +1,82 @@
+use crate::elements::path::{shapes, PathShape};
+use crate::SvgElement;
+use std::collections::HashMap;
+
+pub struct Rect {
+    position: (f32, f32),               // x, y
+    size: (f32, f32),                   // width, height
+    radius: (Option<f32>, Option<f32>), // rx, ry; `None` is auto
+    attributes: HashMap<String, String>,
+    value: Option<String>,
+}
+
+impl Rect {
+    pub fn new(position: (f32, f32), size: (f32, f32)) -> Self {
+        Self {
+            position,
+            size,
+            radius: (None, None),
+            attributes: HashMap::new(),
+            value: None,
+        }
+    }
+
+    pub fn with_radius(mut self, rx: Option<f32>, ry: Option<f32>) -> Self {
+        self.radius = (rx, ry);
+        self
+    }
+
+    pub fn position(&self) -> (f32, f32) {
+        self.position
+    }
+
+    pub fn size(&self) -> (f32, f32) {
+        self.size
+    }
+
+    /// Used corner radii, where an auto radius takes the value of the other one.
+    pub fn used_radius(&self) -> (f32, f32) {
+        match self.radius {
+            (Some(rx), Some(ry)) => (rx, ry),
+            (Some(r), None) | (None, Some(r)) => (r, r),
+            (None, None) => (0., 0.),
+        }
+    }
+
+    pub fn to_path_shape(&self) -> PathShape {
+        shapes::rounded_rect(self.position, self.size, self.used_radius())
+    }
+}
+
+impl SvgElement for Rect {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        "rect"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![
+            ("x".to_string(), Some(self.position.0.to_string())),
+            ("y".to_string(), Some(self.position.1.to_string())),
+            ("width".to_string(), Some(self.size.0.to_string())),
+            ("height".to_string(), Some(self.size.1.to_string())),
+            ("rx".to_string(), self.radius.0.map(|r| r.to_string())),
+            ("ry".to_string(), self.radius.1.map(|r| r.to_string())),
+        ]
+    }
+
+    fn value(&self) -> Option<&String> {
+        self.value.as_ref()
+    }
+
+    fn path_shape(&self) -> Option<PathShape> {
+        Some(self.to_path_shape())
+    }
+}
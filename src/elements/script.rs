@@ -0,0 +1,35 @@
+use crate::{Node, SvgElement};
+use std::collections::HashMap;
+
+/// `<script>` element embedding a script as a CDATA section.
+pub struct Script {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+}
+
+impl Script {
+    pub fn new(code: &str) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            children: vec![Node::CData(code.to_string())],
+        }
+    }
+}
+
+impl SvgElement for Script {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "script"
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{Node, SvgElement};
+use std::collections::HashMap;
+
+/// `<style>` element embedding a stylesheet as a CDATA section.
+pub struct Style {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+}
+
+impl Style {
+    pub fn new(css: &str) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            children: vec![Node::CData(css.to_string())],
+        }
+    }
+}
+
+impl SvgElement for Style {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "style"
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{Node, SvgElement};
+use std::collections::HashMap;
+
+/// `<text>` element; further content such as `<tspan>`s is added as children.
+pub struct Text {
+    position: (f32, f32), // x, y
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+}
+
+impl Text {
+    pub fn new(position: (f32, f32), content: &str) -> Self {
+        Self {
+            position,
+            attributes: HashMap::new(),
+            children: vec![Node::Text(content.to_string())],
+        }
+    }
+
+    pub fn position(&self) -> (f32, f32) {
+        self.position
+    }
+
+    pub fn with_child(mut self, child: impl Into<Node>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    pub fn children_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.children
+    }
+}
+
+impl SvgElement for Text {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![
+            ("x".to_string(), Some(self.position.0.to_string())),
+            ("y".to_string(), Some(self.position.1.to_string())),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::style::Style;
+    use crate::serializer::{SerializeOptions, Serializer};
+
+    #[test]
+    fn test_text_content() {
+        let text = Text::new((1., 2.), "a < b").with_child(Node::Comment(" note ".to_string()));
+        let serializer = Serializer::new(SerializeOptions::default());
+        assert_eq!(
+            serializer.serialize(&text),
+            "<text x=\"1\" y=\"2\">a &lt; b<!-- note --></text>"
+        );
+        assert_eq!(
+            serializer.serialize(&Style::new("a > b { x: y } ]]>")),
+            "<style><![CDATA[a > b { x: y } ]]]]><![CDATA[>]]></style>"
+        );
+        let mut out = vec![];
+        let mut writer = crate::writer::SvgWriter::new(&mut out, SerializeOptions::pretty());
+        writer.element(&text).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            Serializer::new(SerializeOptions::pretty()).serialize(&text)
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{Node, SvgElement};
+use std::collections::HashMap;
+
+/// `<title>` element giving the accessible name of its parent.
+pub struct Title {
+    attributes: HashMap<String, String>,
+    children: Vec<Node>,
+}
+
+impl Title {
+    pub fn new(title: &str) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            children: vec![Node::Text(title.to_string())],
+        }
+    }
+}
+
+impl SvgElement for Title {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn name(&self) -> &str {
+        "title"
+    }
+}
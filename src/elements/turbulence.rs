@@ -0,0 +1,85 @@
+use crate::elements::attribute::{
+    Attr, BaseFrequency, NumOctaves, Seed, StitchTiles, TurbulenceType,
+};
+use crate::elements::filter::FilterPrimitive;
+use crate::filter::turbulence;
+use crate::render::raster::Canvas;
+use crate::{SvgElement, UkkoResult};
+use std::collections::HashMap;
+
+/// `<feTurbulence>` primitive filling its subregion with noise.
+#[derive(Default)]
+pub struct Turbulence {
+    attributes: HashMap<String, String>,
+    noise: turbulence::Turbulence,
+}
+
+impl Turbulence {
+    pub fn new(kind: TurbulenceType) -> Self {
+        Self {
+            attributes: HashMap::new(),
+            noise: turbulence::Turbulence::new(kind),
+        }
+    }
+
+    pub fn with_base_frequency(mut self, base_frequency: BaseFrequency) -> UkkoResult<Self> {
+        self.noise = self.noise.with_base_frequency(base_frequency)?;
+        Ok(self)
+    }
+
+    pub fn with_num_octaves(mut self, num_octaves: NumOctaves) -> Self {
+        self.noise = self.noise.with_num_octaves(num_octaves);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: Seed) -> Self {
+        self.noise = self.noise.with_seed(seed);
+        self
+    }
+
+    pub fn with_stitch_tiles(mut self, stitch_tiles: StitchTiles) -> Self {
+        self.noise = self.noise.with_stitch_tiles(stitch_tiles);
+        self
+    }
+
+    pub fn noise(&self) -> &turbulence::Turbulence {
+        &self.noise
+    }
+
+    /// Renders the noise of a `width` by `height` subregion at `origin`.
+    pub fn render(&self, origin: (f64, f64), width: usize, height: usize) -> Canvas {
+        self.noise.render(origin, width, height)
+    }
+}
+
+impl SvgElement for Turbulence {
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.attributes
+    }
+
+    fn name(&self) -> &str {
+        "feTurbulence"
+    }
+
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        let typed = |attr: &dyn Attr| (attr.name(), Some(attr.value()));
+        vec![
+            typed(&self.noise.base_frequency()),
+            typed(&self.noise.num_octaves()),
+            typed(&self.noise.seed()),
+            typed(&self.noise.stitch_tiles()),
+            typed(&self.noise.kind()),
+        ]
+    }
+}
+
+impl FilterPrimitive for Turbulence {
+    /// Noise depends only on the position, not on any input.
+    fn input_count(&self) -> usize {
+        0
+    }
+}
@@ -1,10 +1,16 @@
 pub mod color;
 pub mod display;
+pub mod filter;
+pub mod parse;
 pub mod position;
+pub mod shape;
+pub mod transform;
 
 use crate::elements::value::position::Position;
+use crate::UkkoError;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use std::time::Duration;
 use time::OffsetDateTime;
 
@@ -16,7 +22,10 @@ fn format_iso8601_extended(dt: OffsetDateTime) -> String {
     dt.format(&format).expect("Formatting failed")
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+/// Bounds as `((min_x, min_y), (max_x, max_y))`.
+pub type Bounds = ((f32, f32), (f32, f32));
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Length(f64, Option<LengthUnit>);
 
 impl Display for Length {
@@ -30,7 +39,7 @@ impl Display for Length {
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum LengthUnit {
     Pixels,
     Points,
@@ -105,6 +114,44 @@ impl Display for LengthUnit {
     }
 }
 
+impl LengthUnit {
+    const ALL: [LengthUnit; 15] = [
+        LengthUnit::Pixels,
+        LengthUnit::Points,
+        LengthUnit::Picas,
+        LengthUnit::Inches,
+        LengthUnit::QuarterMillimeters,
+        LengthUnit::Millimeters,
+        LengthUnit::Centimeters,
+        LengthUnit::FontSize,
+        LengthUnit::FontXSize,
+        LengthUnit::CharacterAdvance0,
+        LengthUnit::RootElementFontSize,
+        LengthUnit::ViewPort1PercentWidth,
+        LengthUnit::ViewPort1PercentHeight,
+        LengthUnit::ViewPort1PercentMin,
+        LengthUnit::ViewPort1PercentMax,
+    ];
+}
+
+impl FromStr for Length {
+    type Err = UkkoError;
+
+    /// Parses a number followed by an optional unit, such as `2.5em`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        for unit in LengthUnit::ALL {
+            let number = s.strip_suffix(&unit.to_string()).map(str::parse::<f64>);
+            if let Some(Ok(number)) = number {
+                return Ok(Length(number, Some(unit)));
+            }
+        }
+        s.parse::<f64>()
+            .map(|number| Length(number, None))
+            .map_err(|_| UkkoError::parse(format!("Invalid length `{}`.", s)))
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ClockValue(Duration);
 
@@ -402,6 +449,40 @@ impl Display for LengthPercentage {
     }
 }
 
+/// Coordinate or size of a filter region or primitive subregion.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RegionLength {
+    /// User units, or a fraction of the bounding box with `objectBoundingBox`.
+    Number(f64),
+    Percentage(f64),
+}
+
+impl Display for RegionLength {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegionLength::Number(n) => write!(f, "{}", n),
+            RegionLength::Percentage(p) => write!(f, "{}%", p),
+        }
+    }
+}
+
+impl RegionLength {
+    /// Resolves the length along one axis. Relative to the bounding box
+    /// `(origin, size)` when `bounding_box`, else in user units with
+    /// percentages of the viewport `size`.
+    pub(crate) fn resolve(&self, origin: f32, size: f32, viewport: f32, bounding_box: bool) -> f32 {
+        let fraction = match *self {
+            RegionLength::Number(n) if !bounding_box => return n as f32,
+            RegionLength::Number(n) => n as f32,
+            RegionLength::Percentage(p) => p as f32 / 100.,
+        };
+        match bounding_box {
+            true => origin + fraction * size,
+            false => fraction * viewport,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum LengthPercentageAuto {
     LengthPercentage(LengthPercentage),
@@ -571,8 +652,9 @@ impl Display for RadialSize {
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum FillRule {
+    #[default]
     NonZero,
     EvenOdd,
 }
@@ -594,6 +676,18 @@ impl Display for FillRule {
     }
 }
 
+impl FromStr for FillRule {
+    type Err = UkkoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "nonzero" => Ok(FillRule::NonZero),
+            "evenodd" => Ok(FillRule::EvenOdd),
+            _ => Err(UkkoError::parse(format!("Invalid fill rule: {}", s))),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum BasicShape {
     BasicShapeRect(BasicShapeRect),
@@ -1,7 +1,10 @@
+use crate::elements::value::parse::{parse_color, split_arguments};
+use crate::{Color, UkkoError, UkkoResult};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum HexColor {
     Three(u8, u8, u8),
     Four(u8, u8, u8, u8),
@@ -38,7 +41,7 @@ impl Display for HexColor {
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum RectangularColorSpace {
     Srgb,
     SrgbLinear,
@@ -97,7 +100,7 @@ impl Display for RectangularColorSpace {
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum PolarColorSpace {
     Hsl,
     Hwb,
@@ -128,7 +131,7 @@ impl Display for PolarColorSpace {
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum HueInterpolationMethod {
     Shorter,
     Longer,
@@ -159,7 +162,7 @@ impl Display for HueInterpolationMethod {
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ColorInterpolationMethod {
     RectangularColorSpace(RectangularColorSpace),
     PolarColorSpace(PolarColorSpace, Option<HueInterpolationMethod>),
@@ -186,7 +189,7 @@ impl Display for ColorInterpolationMethod {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum CssColor {
     Keyword(String),
     Hex(HexColor),
@@ -232,3 +235,190 @@ impl Display for CssColor {
         }
     }
 }
+
+impl CssColor {
+    /// The color as RGB and opacity, if it can be evaluated. `currentColor`
+    /// resolves to `current_color`, `light-dark()` to its light color, and
+    /// `color-mix()` is approximated by mixing premultiplied sRGB.
+    pub fn resolve(&self, current_color: (Color, f32)) -> Option<(Color, f32)> {
+        match self {
+            CssColor::Keyword(keyword) => parse_color(keyword, current_color),
+            CssColor::Hex(hex) => {
+                let nibble = |v: u8| v.min(15) * 17;
+                let (rgb, alpha) = match *hex {
+                    HexColor::Three(r, g, b) => ([nibble(r), nibble(g), nibble(b)], 255),
+                    HexColor::Four(r, g, b, a) => ([nibble(r), nibble(g), nibble(b)], nibble(a)),
+                    HexColor::Six(r, g, b) => ([r, g, b], 255),
+                    HexColor::Eight(r, g, b, a) => ([r, g, b], a),
+                };
+                Some((Color::from(rgb), alpha as f32 / 255.))
+            }
+            CssColor::ColorMix(_, c1, p1, c2, p2) => {
+                let (c1, a1) = c1.resolve(current_color)?;
+                let (c2, a2) = c2.resolve(current_color)?;
+                let (p1, p2) = match (p1, p2) {
+                    (None, None) => (50., 50.),
+                    (Some(p1), None) => (*p1, 100. - p1),
+                    (None, Some(p2)) => (100. - p2, *p2),
+                    (Some(p1), Some(p2)) => (*p1, *p2),
+                };
+                let total = p1 + p2;
+                if total <= 0. {
+                    return None;
+                }
+                let (w1, w2) = ((p1 / total) as f32, (p2 / total) as f32);
+                let alpha = a1 * w1 + a2 * w2;
+                if alpha == 0. {
+                    return Some((Color::BLACK, 0.));
+                }
+                let mix = |v1: f32, v2: f32| (v1 * a1 * w1 + v2 * a2 * w2) / alpha;
+                let color = Color::new(mix(c1.r, c2.r), mix(c1.g, c2.g), mix(c1.b, c2.b));
+                // Percentages adding up to less than 100% make the mix transparent.
+                Some((color, alpha * (total.min(100.) / 100.) as f32))
+            }
+            CssColor::LightDark(light, _) => light.resolve(current_color),
+        }
+    }
+}
+
+const RECTANGULAR_COLOR_SPACES: [RectangularColorSpace; 11] = [
+    RectangularColorSpace::Srgb,
+    RectangularColorSpace::SrgbLinear,
+    RectangularColorSpace::DisplayP3,
+    RectangularColorSpace::A98Rgb,
+    RectangularColorSpace::ProphotoRgb,
+    RectangularColorSpace::Rec2020,
+    RectangularColorSpace::Lab,
+    RectangularColorSpace::OkLab,
+    RectangularColorSpace::Xyz,
+    RectangularColorSpace::XyzD50,
+    RectangularColorSpace::XyzD65,
+];
+
+const POLAR_COLOR_SPACES: [PolarColorSpace; 4] = [
+    PolarColorSpace::Hsl,
+    PolarColorSpace::Hwb,
+    PolarColorSpace::Lch,
+    PolarColorSpace::OkLch,
+];
+
+const HUE_INTERPOLATION_METHODS: [HueInterpolationMethod; 4] = [
+    HueInterpolationMethod::Shorter,
+    HueInterpolationMethod::Longer,
+    HueInterpolationMethod::Increasing,
+    HueInterpolationMethod::Decreasing,
+];
+
+impl FromStr for ColorInterpolationMethod {
+    type Err = UkkoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || UkkoError::parse(format!("Invalid color interpolation method `{}`.", s));
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        let (Some(&"in"), Some(space)) = (words.first(), words.get(1)) else {
+            return Err(error());
+        };
+        let hue = words[2..].join(" ");
+        if let Some(space) = RECTANGULAR_COLOR_SPACES
+            .into_iter()
+            .find(|r| r.to_string() == *space)
+        {
+            return match hue.is_empty() {
+                true => Ok(ColorInterpolationMethod::RectangularColorSpace(space)),
+                false => Err(error()),
+            };
+        }
+        let space = POLAR_COLOR_SPACES
+            .into_iter()
+            .find(|p| p.to_string() == *space)
+            .ok_or_else(error)?;
+        let hue = match hue.is_empty() {
+            true => None,
+            false => Some(
+                HUE_INTERPOLATION_METHODS
+                    .into_iter()
+                    .find(|h| h.to_string() == hue)
+                    .ok_or_else(error)?,
+            ),
+        };
+        Ok(ColorInterpolationMethod::PolarColorSpace(space, hue))
+    }
+}
+
+/// Splits `<color> <percentage>?` in either order.
+fn parse_mix_component(value: &str) -> UkkoResult<(CssColor, Option<f64>)> {
+    let (mut color, mut percentage) = (None, None);
+    for part in split_arguments(value, char::is_whitespace) {
+        match part.strip_suffix('%').map(str::parse::<f64>) {
+            Some(Ok(p)) if percentage.is_none() => percentage = Some(p),
+            _ if color.is_none() => color = Some(part.parse::<CssColor>()?),
+            _ => {
+                return Err(UkkoError::parse(format!(
+                    "Invalid color-mix() color `{}`.",
+                    value
+                )))
+            }
+        }
+    }
+    let color = color.ok_or(UkkoError::parse("color-mix() needs two colors."))?;
+    Ok((color, percentage))
+}
+
+impl FromStr for CssColor {
+    type Err = UkkoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let error = || UkkoError::parse(format!("Invalid color `{}`.", s));
+        let lower = s.to_ascii_lowercase();
+        let function = |name: &str| {
+            lower
+                .strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('('))
+                .and_then(|rest| rest.strip_suffix(')'))
+                .map(|_| split_arguments(&s[name.len() + 1..s.len() - 1], |c| c == ','))
+        };
+        if let Some(args) = function("color-mix") {
+            let [method, first, second] = args[..] else {
+                return Err(error());
+            };
+            let (c1, p1) = parse_mix_component(first)?;
+            let (c2, p2) = parse_mix_component(second)?;
+            return Ok(CssColor::ColorMix(
+                method.parse()?,
+                Box::new(c1),
+                p1,
+                Box::new(c2),
+                p2,
+            ));
+        }
+        if let Some(args) = function("light-dark") {
+            let [light, dark] = args[..] else {
+                return Err(error());
+            };
+            return Ok(CssColor::LightDark(
+                Box::new(light.parse()?),
+                Box::new(dark.parse()?),
+            ));
+        }
+        if let Some(hex) = s.strip_prefix('#') {
+            let digits = hex
+                .chars()
+                .map(|c| c.to_digit(16).map(|d| d as u8))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(error)?;
+            let byte = |i: usize| digits[i] * 16 + digits[i + 1];
+            return Ok(CssColor::Hex(match digits.len() {
+                3 => HexColor::Three(digits[0], digits[1], digits[2]),
+                4 => HexColor::Four(digits[0], digits[1], digits[2], digits[3]),
+                6 => HexColor::Six(byte(0), byte(2), byte(4)),
+                8 => HexColor::Eight(byte(0), byte(2), byte(4), byte(6)),
+                _ => return Err(error()),
+            }));
+        }
+        match parse_color(s, (Color::BLACK, 1.)) {
+            Some(_) => Ok(CssColor::Keyword(s.to_string())),
+            None => Err(error()),
+        }
+    }
+}
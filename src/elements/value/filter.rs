@@ -0,0 +1,503 @@
+use crate::elements::attribute::{
+    Attr, Channel, ColorInterpolationFilter, CompositeOperator, EdgeMode, Intercept, Slope,
+    StdDeviation, TableValues,
+};
+use crate::elements::component_transfer::ComponentTransfer;
+use crate::elements::filter::{Filter, FilterInput, Merge, Primitive, PrimitiveKind};
+use crate::elements::value::color::CssColor;
+use crate::elements::value::parse::split_arguments;
+use crate::elements::value::{Length, LengthUnit};
+use crate::filter::color_matrix::ColorMatrix;
+use crate::filter::component_transfer::TransferFunction;
+use crate::{Color, SvgElement, UkkoError, UkkoResult};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// One function of the CSS `filter` property. Amounts are numbers, with
+/// percentages divided by 100, and angles are in degrees.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterFunction {
+    Blur(Length),
+    Brightness(f64),
+    Contrast(f64),
+    DropShadow {
+        dx: Length,
+        dy: Length,
+        std_deviation: Length,
+        /// `None` when omitted, which means `currentColor`.
+        color: Option<CssColor>,
+    },
+    Grayscale(f64),
+    HueRotate(f64),
+    Invert(f64),
+    Opacity(f64),
+    Saturate(f64),
+    Sepia(f64),
+    Url(String),
+}
+
+/// Value of the CSS `filter` property.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum CssFilter {
+    #[default]
+    None,
+    Functions(Vec<FilterFunction>),
+}
+
+fn parse_number(value: &str) -> UkkoResult<f64> {
+    value
+        .parse::<f64>()
+        .map_err(|_| UkkoError::parse(format!("Invalid number `{}`.", value)))
+}
+
+/// A number or percentage; negative amounts are invalid.
+fn parse_amount(value: &str) -> UkkoResult<f64> {
+    let amount = match value.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.,
+        None => parse_number(value)?,
+    };
+    match amount < 0. {
+        true => Err(UkkoError::parse("Filter amounts must not be negative.")),
+        false => Ok(amount),
+    }
+}
+
+/// A length with a unit, which may only be omitted for zero.
+fn parse_length(value: &str) -> UkkoResult<Length> {
+    match value.parse::<Length>()? {
+        Length(number, None) if number != 0. => Err(UkkoError::parse(format!(
+            "Missing unit in length `{}`.",
+            value
+        ))),
+        length => Ok(length),
+    }
+}
+
+/// Zero pixels, the default of omitted lengths.
+const ZERO: Length = Length(0., Some(LengthUnit::Pixels));
+
+fn parse_angle(value: &str) -> UkkoResult<f64> {
+    for (unit, degrees) in [
+        ("deg", 1.),
+        ("grad", 0.9),
+        ("rad", 180. / std::f64::consts::PI),
+        ("turn", 360.),
+    ] {
+        if let Some(number) = value.strip_suffix(unit) {
+            return Ok(parse_number(number)? * degrees);
+        }
+    }
+    match parse_number(value)? == 0. {
+        true => Ok(0.),
+        false => Err(UkkoError::parse(format!("Invalid angle `{}`.", value))),
+    }
+}
+
+fn parse_drop_shadow(args: &str) -> UkkoResult<FilterFunction> {
+    let (mut lengths, mut color) = (vec![], None);
+    for part in split_arguments(args, char::is_whitespace) {
+        match parse_length(part) {
+            Ok(length) => lengths.push(length),
+            Err(_) if color.is_none() => color = Some(part.parse::<CssColor>()?),
+            Err(error) => return Err(error),
+        }
+    }
+    if !(2..=3).contains(&lengths.len()) {
+        return Err(UkkoError::parse(
+            "drop-shadow() needs two or three lengths.",
+        ));
+    }
+    Ok(FilterFunction::DropShadow {
+        dx: lengths[0],
+        dy: lengths[1],
+        std_deviation: lengths.get(2).copied().unwrap_or(ZERO),
+        color,
+    })
+}
+
+impl FilterFunction {
+    fn from_function(name: &str, args: &str) -> UkkoResult<Self> {
+        let args = args.trim();
+        let amount = || match args.is_empty() {
+            true => Ok(1.),
+            false => parse_amount(args),
+        };
+        // Amounts beyond 100% are clamped for these functions.
+        let clamped = || amount().map(|a| a.min(1.));
+        Ok(match name.to_ascii_lowercase().as_str() {
+            "blur" if args.is_empty() => FilterFunction::Blur(ZERO),
+            "blur" => FilterFunction::Blur(parse_length(args)?),
+            "brightness" => FilterFunction::Brightness(amount()?),
+            "contrast" => FilterFunction::Contrast(amount()?),
+            "drop-shadow" => parse_drop_shadow(args)?,
+            "grayscale" => FilterFunction::Grayscale(clamped()?),
+            "hue-rotate" if args.is_empty() => FilterFunction::HueRotate(0.),
+            "hue-rotate" => FilterFunction::HueRotate(parse_angle(args)?),
+            "invert" => FilterFunction::Invert(clamped()?),
+            "opacity" => FilterFunction::Opacity(clamped()?),
+            "saturate" => FilterFunction::Saturate(amount()?),
+            "sepia" => FilterFunction::Sepia(clamped()?),
+            "url" => FilterFunction::Url(args.trim_matches(['"', '\'']).to_string()),
+            _ => {
+                return Err(UkkoError::parse(format!(
+                    "Unknown filter function `{}`.",
+                    name
+                )))
+            }
+        })
+    }
+
+    /// Appends the primitives equivalent to the function, reading `input`
+    /// and naming the output `result`.
+    fn expand(
+        &self,
+        filter: Filter,
+        input: FilterInput,
+        result: &str,
+        current_color: Color,
+        viewport: (f64, f64),
+    ) -> UkkoResult<Filter> {
+        let px = |length: &Length| length.to_user_units(viewport);
+        let linear = |slope: f64, intercept: f64| TransferFunction::Linear {
+            slope: Slope(slope),
+            intercept: Intercept(intercept),
+        };
+        let rgb = |f: TransferFunction| {
+            Primitive::new(
+                ComponentTransfer::new()
+                    .with_function(Channel::R, f.clone())
+                    .with_function(Channel::G, f.clone())
+                    .with_function(Channel::B, f),
+            )
+        };
+        let primitive = match self {
+            FilterFunction::Blur(radius) => Primitive::from(PrimitiveKind::GaussianBlur {
+                std_deviation: StdDeviation(px(radius), None),
+                edge_mode: EdgeMode::None,
+            }),
+            &FilterFunction::Brightness(amount) => rgb(linear(amount, 0.)),
+            &FilterFunction::Contrast(amount) => rgb(linear(amount, (1. - amount) / 2.)),
+            FilterFunction::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+            } => {
+                let current_color = (current_color, 1.);
+                let color = match color {
+                    Some(color) => color.resolve(current_color).ok_or_else(|| {
+                        UkkoError::parse(format!("Cannot resolve color `{}`.", color))
+                    })?,
+                    None => current_color,
+                };
+                return drop_shadow(
+                    filter,
+                    input,
+                    result,
+                    (px(dx), px(dy), px(std_deviation)),
+                    color,
+                );
+            }
+            &FilterFunction::Grayscale(amount) => {
+                color_matrix(ColorMatrix::Matrix(grayscale_matrix(amount)))
+            }
+            &FilterFunction::HueRotate(angle) => color_matrix(ColorMatrix::HueRotate(angle)),
+            &FilterFunction::Invert(amount) => {
+                let table = TableValues(vec![amount, 1. - amount]);
+                rgb(TransferFunction::Table(table))
+            }
+            &FilterFunction::Opacity(amount) => {
+                Primitive::new(ComponentTransfer::new().with_function(
+                    Channel::A,
+                    TransferFunction::Table(TableValues(vec![0., amount])),
+                ))
+            }
+            &FilterFunction::Saturate(amount) => color_matrix(ColorMatrix::Saturate(amount)),
+            &FilterFunction::Sepia(amount) => {
+                color_matrix(ColorMatrix::Matrix(sepia_matrix(amount)))
+            }
+            FilterFunction::Url(url) => {
+                return Err(UkkoError::parse(format!("Cannot expand url({}).", url)))
+            }
+        };
+        filter.with_primitive(primitive.with_input(input).with_result(result))
+    }
+}
+
+fn color_matrix(matrix: ColorMatrix) -> Primitive {
+    Primitive::from(PrimitiveKind::ColorMatrix(matrix))
+}
+
+#[rustfmt::skip]
+fn grayscale_matrix(amount: f64) -> [f64; 20] {
+    let a = 1. - amount;
+    [
+        0.2126 + 0.7874 * a, 0.7152 - 0.7152 * a, 0.0722 - 0.0722 * a, 0., 0.,
+        0.2126 - 0.2126 * a, 0.7152 + 0.2848 * a, 0.0722 - 0.0722 * a, 0., 0.,
+        0.2126 - 0.2126 * a, 0.7152 - 0.7152 * a, 0.0722 + 0.9278 * a, 0., 0.,
+        0., 0., 0., 1., 0.,
+    ]
+}
+
+#[rustfmt::skip]
+fn sepia_matrix(amount: f64) -> [f64; 20] {
+    let a = 1. - amount;
+    [
+        0.393 + 0.607 * a, 0.769 - 0.769 * a, 0.189 - 0.189 * a, 0., 0.,
+        0.349 - 0.349 * a, 0.686 + 0.314 * a, 0.168 - 0.168 * a, 0., 0.,
+        0.272 - 0.272 * a, 0.534 - 0.534 * a, 0.131 + 0.869 * a, 0., 0.,
+        0., 0., 0., 1., 0.,
+    ]
+}
+
+/// Blurred, offset and colored copy of the alpha of `input`, merged below it.
+fn drop_shadow(
+    filter: Filter,
+    input: FilterInput,
+    result: &str,
+    (dx, dy, std_deviation): (f64, f64, f64),
+    (color, opacity): (Color, f32),
+) -> UkkoResult<Filter> {
+    let offset = format!("{}-offset", result);
+    let shadow = format!("{}-shadow", result);
+    filter
+        .with_primitive(
+            Primitive::from(PrimitiveKind::GaussianBlur {
+                std_deviation: StdDeviation(std_deviation, None),
+                edge_mode: EdgeMode::None,
+            })
+            .with_input(input.clone()),
+        )?
+        .with_primitive(Primitive::from(PrimitiveKind::Offset { dx, dy }).with_result(&offset))?
+        .with_primitive(Primitive::from(PrimitiveKind::Flood {
+            color,
+            opacity: opacity as f64,
+        }))?
+        .with_primitive(
+            Primitive::from(PrimitiveKind::Composite(CompositeOperator::In))
+                .with_input2(FilterInput::Reference(offset))
+                .with_result(&shadow),
+        )?
+        .with_primitive(
+            Primitive::new(Merge::new(vec![FilterInput::Reference(shadow), input]))
+                .with_result(result),
+        )
+}
+
+impl CssFilter {
+    /// Expands the functions into an equivalent `<filter>` with SVG 1.1
+    /// primitives, chained in order and computed in sRGB like CSS filters.
+    /// `current_color` colors shadows without an explicit color, and
+    /// viewport-relative lengths are taken relative to `viewport`. Returns
+    /// `None` for `none`, and fails on `url()`, which has no expansion.
+    pub fn to_svg_filter(
+        &self,
+        current_color: Color,
+        viewport: (f64, f64),
+    ) -> UkkoResult<Option<Filter>> {
+        let CssFilter::Functions(functions) = self else {
+            return Ok(None);
+        };
+        let mut filter = Filter::new();
+        let srgb = ColorInterpolationFilter::Srgb;
+        filter.with_attribute(srgb.name(), srgb.value());
+        let mut input = FilterInput::SourceGraphic;
+        for (i, function) in functions.iter().enumerate() {
+            let result = format!("filter{}", i);
+            filter = function.expand(filter, input, &result, current_color, viewport)?;
+            input = FilterInput::Reference(result);
+        }
+        Ok(Some(filter))
+    }
+}
+
+impl FromStr for CssFilter {
+    type Err = UkkoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("none") {
+            return Ok(CssFilter::None);
+        }
+        let functions = split_arguments(s, char::is_whitespace)
+            .into_iter()
+            .map(|part| {
+                let open = part
+                    .find('(')
+                    .ok_or(UkkoError::parse("Missing '(' in filter function."))?;
+                let args = part[open + 1..]
+                    .strip_suffix(')')
+                    .ok_or(UkkoError::parse("Missing ')' in filter function."))?;
+                FilterFunction::from_function(part[..open].trim(), args)
+            })
+            .collect::<UkkoResult<Vec<_>>>()?;
+        match functions.is_empty() {
+            true => Err(UkkoError::parse("Empty filter value.")),
+            false => Ok(CssFilter::Functions(functions)),
+        }
+    }
+}
+
+impl Display for FilterFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterFunction::Blur(radius) => write!(f, "blur({})", radius),
+            FilterFunction::Brightness(a) => write!(f, "brightness({})", a),
+            FilterFunction::Contrast(a) => write!(f, "contrast({})", a),
+            FilterFunction::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+            } => {
+                write!(f, "drop-shadow(")?;
+                if let Some(color) = color {
+                    write!(f, "{} ", color)?;
+                }
+                write!(f, "{} {} {})", dx, dy, std_deviation)
+            }
+            FilterFunction::Grayscale(a) => write!(f, "grayscale({})", a),
+            FilterFunction::HueRotate(angle) => write!(f, "hue-rotate({}deg)", angle),
+            FilterFunction::Invert(a) => write!(f, "invert({})", a),
+            FilterFunction::Opacity(a) => write!(f, "opacity({})", a),
+            FilterFunction::Saturate(a) => write!(f, "saturate({})", a),
+            FilterFunction::Sepia(a) => write!(f, "sepia({})", a),
+            FilterFunction::Url(url) => write!(f, "url({})", url),
+        }
+    }
+}
+
+impl Display for CssFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CssFilter::None => write!(f, "none"),
+            CssFilter::Functions(functions) => write!(
+                f,
+                "{}",
+                functions
+                    .iter()
+                    .map(|function| function.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        }
+    }
+}
+
+impl Attr for CssFilter {
+    fn name(&self) -> String {
+        "filter".to_string()
+    }
+
+    fn value(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let filter = "blur(2px) brightness(150%) drop-shadow(rgb(255, 0, 0) 1px 2px) \
+            hue-rotate(0.5turn) invert() url(#f)"
+            .parse::<CssFilter>()
+            .unwrap();
+        let CssFilter::Functions(functions) = &filter else {
+            panic!("expected functions");
+        };
+        let px = |v: f64| Length(v, Some(LengthUnit::Pixels));
+        assert_eq!(functions[0], FilterFunction::Blur(px(2.)));
+        assert_eq!(functions[1], FilterFunction::Brightness(1.5));
+        assert_eq!(
+            functions[2],
+            FilterFunction::DropShadow {
+                dx: px(1.),
+                dy: px(2.),
+                std_deviation: px(0.),
+                color: Some(CssColor::Keyword("rgb(255, 0, 0)".to_string())),
+            }
+        );
+        assert_eq!(functions[3], FilterFunction::HueRotate(180.));
+        assert_eq!(functions[4], FilterFunction::Invert(1.));
+        assert_eq!(functions[5], FilterFunction::Url("#f".to_string()));
+        assert_eq!(
+            filter.to_string(),
+            "blur(2px) brightness(1.5) drop-shadow(rgb(255, 0, 0) 1px 2px 0px) hue-rotate(180deg) invert(1) url(#f)"
+        );
+        assert_eq!("none".parse::<CssFilter>().unwrap(), CssFilter::None);
+        assert_eq!(
+            "grayscale(250%)".parse::<CssFilter>().unwrap(),
+            CssFilter::Functions(vec![FilterFunction::Grayscale(1.)])
+        );
+        for invalid in [
+            "blur(2)",
+            "sepia(-1)",
+            "drop-shadow(1px)",
+            "glow(1)",
+            "blur(1px",
+            "drop-shadow(1px 2px nocolor)",
+        ] {
+            assert!(invalid.parse::<CssFilter>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        let filter = "sepia(1) drop-shadow(2px 3px 4px)"
+            .parse::<CssFilter>()
+            .unwrap()
+            .to_svg_filter(Color::BLUE, (100., 100.))
+            .unwrap()
+            .unwrap();
+        assert_eq!(filter.attributes()["color-interpolation-filters"], "sRGB");
+        let names = filter
+            .children()
+            .iter()
+            .map(|c| c.as_element().unwrap().name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "feColorMatrix",
+                "feGaussianBlur",
+                "feOffset",
+                "feFlood",
+                "feComposite",
+                "feMerge"
+            ]
+        );
+        let blur = filter.children()[1].as_element().unwrap();
+        assert_eq!(blur.all_attributes()["in"], "filter0");
+        assert_eq!(blur.all_attributes()["stdDeviation"], "4");
+        let flood = filter.children()[3].as_element().unwrap();
+        assert_eq!(flood.all_attributes()["flood-color"], "#0000FF");
+        let merge = filter.children()[5].as_element().unwrap();
+        assert_eq!(
+            merge.children()[1].as_element().unwrap().all_attributes()["in"],
+            "filter0"
+        );
+
+        let filter = "blur(1em) drop-shadow(color-mix(in srgb, red, blue 25%) 3pt 0 2vw)"
+            .parse::<CssFilter>()
+            .unwrap()
+            .to_svg_filter(Color::BLACK, (200., 100.))
+            .unwrap()
+            .unwrap();
+        let attribute = |i: usize, name: &str| {
+            filter.children()[i].as_element().unwrap().all_attributes()[name].clone()
+        };
+        assert_eq!(attribute(0, "stdDeviation"), "16");
+        assert_eq!(attribute(1, "stdDeviation"), "4");
+        assert_eq!(attribute(2, "dx"), "4");
+        assert_eq!(attribute(3, "flood-color"), "#BF003F");
+
+        assert!(CssFilter::None
+            .to_svg_filter(Color::BLACK, (100., 100.))
+            .unwrap()
+            .is_none());
+        let url = CssFilter::Functions(vec![FilterFunction::Url("#f".to_string())]);
+        assert!(url.to_svg_filter(Color::BLACK, (100., 100.)).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use crate::Color;
+
+/// Parses a color: hex notation, `rgb()`/`rgba()`, a basic named color or
+/// `currentColor`. Returns the color and its alpha.
+pub fn parse_color(value: &str, current_color: (Color, f32)) -> Option<(Color, f32)> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<Vec<_>>>()?;
+        let channels = match digits.len() {
+            3 | 4 => digits.iter().map(|d| d * 17).collect::<Vec<_>>(),
+            6 | 8 => digits.chunks(2).map(|d| d[0] * 16 + d[1]).collect(),
+            _ => return None,
+        };
+        let alpha = channels.get(3).map_or(1., |&a| a as f32 / 255.);
+        return Some((
+            Color::from_rgb(channels[0], channels[1], channels[2]),
+            alpha,
+        ));
+    }
+    let lower = value.to_ascii_lowercase();
+    if let Some(args) = lower
+        .strip_prefix("rgba(")
+        .or_else(|| lower.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let parts = args
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>();
+        let component = |part: &str, max: f32| -> Option<f32> {
+            match part.strip_suffix('%') {
+                Some(percent) => Some(percent.parse::<f32>().ok()? / 100.),
+                None => Some(part.parse::<f32>().ok()? / max),
+            }
+        };
+        if parts.len() < 3 || parts.len() > 4 {
+            return None;
+        }
+        let rgb = parts[..3]
+            .iter()
+            .map(|p| component(p, 255.).map(|c| c.clamp(0., 1.)))
+            .collect::<Option<Vec<_>>>()?;
+        let alpha = match parts.get(3) {
+            Some(a) => component(a, 1.)?.clamp(0., 1.),
+            None => 1.,
+        };
+        return Some((Color::new(rgb[0], rgb[1], rgb[2]), alpha));
+    }
+    let rgb = match lower.as_str() {
+        "currentcolor" => return Some(current_color),
+        "transparent" => return Some((Color::BLACK, 0.)),
+        "black" => [0, 0, 0],
+        "white" => [255, 255, 255],
+        "red" => [255, 0, 0],
+        "lime" => [0, 255, 0],
+        "green" => [0, 128, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "cyan" | "aqua" => [0, 255, 255],
+        "magenta" | "fuchsia" => [255, 0, 255],
+        "gray" | "grey" => [128, 128, 128],
+        "silver" => [192, 192, 192],
+        "maroon" => [128, 0, 0],
+        "olive" => [128, 128, 0],
+        "purple" => [128, 0, 128],
+        "teal" => [0, 128, 128],
+        "navy" => [0, 0, 128],
+        "orange" => [255, 165, 0],
+        _ => return None,
+    };
+    Some((Color::from(rgb), 1.))
+}
+
+/// Parses a number, optionally in `px`.
+pub(crate) fn parse_number(value: &str) -> Option<f32> {
+    let value = value.trim();
+    value
+        .strip_suffix("px")
+        .unwrap_or(value)
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Splits `value` where `is_separator` matches outside of parentheses,
+/// trimming the parts and dropping empty ones.
+pub(crate) fn split_arguments(value: &str, is_separator: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if depth == 0 && is_separator(c) => {
+                parts.push(value[start..i].trim());
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(value[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        let current = (Color::BLACK, 1.);
+        let hex = parse_color("#ff000080", current).unwrap();
+        assert_eq!(
+            (hex.0.to_hex(), (hex.1 * 255.).round()),
+            ((255, 0, 0), 128.)
+        );
+        let rgb = parse_color("rgba(0, 255, 0, 0.5)", current).unwrap();
+        assert_eq!((rgb.0.to_hex(), rgb.1), ((0, 255, 0), 0.5));
+        assert_eq!(
+            parse_color("navy", current).unwrap().0.to_hex(),
+            (0, 0, 128)
+        );
+        assert!(parse_color("nonsense", current).is_none());
+    }
+}
@@ -0,0 +1,400 @@
+use crate::elements::path::{shapes, PathShape};
+use crate::elements::value::position::{
+    Position, PositionFour, PositionFourAlignHorizontal, PositionFourAlignVertical,
+    PositionFourBlock, PositionFourInline, PositionFourSimple, PositionOne, PositionTwo,
+    PositionTwoAlignHorizontal, PositionTwoAlignVertical, PositionTwoBlock, PositionTwoInline,
+    PositionTwoSimple,
+};
+use crate::elements::value::transform::Transform;
+use crate::elements::value::{
+    BasicShape, BasicShapeRect, BorderRadius, FillRule, Length, LengthPercentage,
+    LengthPercentageAuto, LengthUnit, RadialExtent, RadialSize,
+};
+use crate::UkkoResult;
+
+/// Font size assumed for font relative units.
+const FONT_SIZE: f64 = 16.;
+
+impl Length {
+    /// Length in user units. Font relative units assume a 16px font and
+    /// viewport units are taken relative to `viewport`.
+    pub fn to_user_units(&self, viewport: (f64, f64)) -> f64 {
+        let factor = match self.1 {
+            None | Some(LengthUnit::Pixels) => 1.,
+            Some(LengthUnit::Points) => 96. / 72.,
+            Some(LengthUnit::Picas) => 16.,
+            Some(LengthUnit::Inches) => 96.,
+            Some(LengthUnit::QuarterMillimeters) => 96. / 101.6,
+            Some(LengthUnit::Millimeters) => 96. / 25.4,
+            Some(LengthUnit::Centimeters) => 96. / 2.54,
+            Some(LengthUnit::FontSize) | Some(LengthUnit::RootElementFontSize) => FONT_SIZE,
+            Some(LengthUnit::FontXSize) | Some(LengthUnit::CharacterAdvance0) => FONT_SIZE / 2.,
+            Some(LengthUnit::ViewPort1PercentWidth) => viewport.0 / 100.,
+            Some(LengthUnit::ViewPort1PercentHeight) => viewport.1 / 100.,
+            Some(LengthUnit::ViewPort1PercentMin) => viewport.0.min(viewport.1) / 100.,
+            Some(LengthUnit::ViewPort1PercentMax) => viewport.0.max(viewport.1) / 100.,
+        };
+        self.0 * factor
+    }
+}
+
+/// Reference box as `(x, y, width, height)`, which also stands in for the
+/// viewport.
+#[derive(Copy, Clone, Debug)]
+struct Reference {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Reference {
+    fn new(reference: ((f32, f32), (f32, f32))) -> Self {
+        let ((x0, y0), (x1, y1)) = reference;
+        Self {
+            x: x0 as f64,
+            y: y0 as f64,
+            width: (x1 - x0) as f64,
+            height: (y1 - y0) as f64,
+        }
+    }
+
+    fn length(&self, length: &Length) -> f64 {
+        length.to_user_units((self.width, self.height))
+    }
+
+    /// Resolves a length, taking percentages of `basis`.
+    fn resolve(&self, value: &LengthPercentage, basis: f64) -> f64 {
+        match value {
+            LengthPercentage::Length(l) => self.length(l),
+            LengthPercentage::Percentage(p) => p * basis,
+        }
+    }
+
+    fn point(&self, x: f64, y: f64) -> (f32, f32) {
+        ((self.x + x) as f32, (self.y + y) as f32)
+    }
+
+    /// Resolves a border radius to horizontal and vertical radii.
+    fn radius(&self, radius: &Option<BorderRadius>) -> (f32, f32) {
+        match radius {
+            Some(BorderRadius(rx, ry)) => {
+                let rx_value = self.resolve(rx, self.width);
+                let ry_value = ry
+                    .map(|ry| self.resolve(&ry, self.height))
+                    .unwrap_or_else(|| match rx {
+                        LengthPercentage::Percentage(p) => p * self.height,
+                        LengthPercentage::Length(_) => rx_value,
+                    });
+                (rx_value as f32, ry_value as f32)
+            }
+            None => (0., 0.),
+        }
+    }
+
+    /// Resolves a position to coordinates relative to the box. The block axis
+    /// is vertical and the inline axis horizontal.
+    fn position(&self, position: &Option<Position>) -> (f64, f64) {
+        let (w, h) = (self.width, self.height);
+        let Some(position) = position else {
+            return (w / 2., h / 2.);
+        };
+        match position {
+            Position::One(one) => match one {
+                PositionOne::Left | PositionOne::XStart | PositionOne::InlineStart => (0., h / 2.),
+                PositionOne::Right | PositionOne::XEnd | PositionOne::InlineEnd => (w, h / 2.),
+                PositionOne::Top | PositionOne::YStart | PositionOne::BlockStart => (w / 2., 0.),
+                PositionOne::Bottom | PositionOne::YEnd | PositionOne::BlockEnd => (w / 2., h),
+                PositionOne::Center => (w / 2., h / 2.),
+                PositionOne::LengthPercentage(lp) => (self.resolve(lp, w), h / 2.),
+            },
+            Position::Two(two) => match two {
+                PositionTwo::Align(horizontal, vertical) => (
+                    match horizontal {
+                        PositionTwoAlignHorizontal::Left | PositionTwoAlignHorizontal::XStart => 0.,
+                        PositionTwoAlignHorizontal::Center => w / 2.,
+                        PositionTwoAlignHorizontal::Right | PositionTwoAlignHorizontal::XEnd => w,
+                        PositionTwoAlignHorizontal::LengthPercentage(lp) => self.resolve(lp, w),
+                    },
+                    match vertical {
+                        PositionTwoAlignVertical::Top | PositionTwoAlignVertical::YStart => 0.,
+                        PositionTwoAlignVertical::Center => h / 2.,
+                        PositionTwoAlignVertical::Bottom | PositionTwoAlignVertical::YEnd => h,
+                        PositionTwoAlignVertical::LengthPercentage(lp) => self.resolve(lp, h),
+                    },
+                ),
+                PositionTwo::BlockInline(block, inline) => (
+                    match inline {
+                        PositionTwoInline::InlineStart => 0.,
+                        PositionTwoInline::Center => w / 2.,
+                        PositionTwoInline::InlineEnd => w,
+                    },
+                    match block {
+                        PositionTwoBlock::BlockStart => 0.,
+                        PositionTwoBlock::Center => h / 2.,
+                        PositionTwoBlock::BlockEnd => h,
+                    },
+                ),
+                PositionTwo::Simple(block, inline) => {
+                    let simple = |s: &PositionTwoSimple, size: f64| match s {
+                        PositionTwoSimple::Start => 0.,
+                        PositionTwoSimple::Center => size / 2.,
+                        PositionTwoSimple::End => size,
+                    };
+                    (simple(inline, w), simple(block, h))
+                }
+            },
+            Position::Four(four) => {
+                let offset = |from_end: bool, lp: &LengthPercentage, size: f64| {
+                    let value = self.resolve(lp, size);
+                    if from_end {
+                        size - value
+                    } else {
+                        value
+                    }
+                };
+                match four {
+                    PositionFour::Align((horizontal, x), (vertical, y)) => (
+                        offset(
+                            matches!(
+                                horizontal,
+                                PositionFourAlignHorizontal::Right
+                                    | PositionFourAlignHorizontal::XEnd
+                            ),
+                            x,
+                            w,
+                        ),
+                        offset(
+                            matches!(
+                                vertical,
+                                PositionFourAlignVertical::Bottom | PositionFourAlignVertical::YEnd
+                            ),
+                            y,
+                            h,
+                        ),
+                    ),
+                    PositionFour::BlockInline((block, y), (inline, x)) => (
+                        offset(matches!(inline, PositionFourInline::InlineEnd), x, w),
+                        offset(matches!(block, PositionFourBlock::BlockEnd), y, h),
+                    ),
+                    PositionFour::Simple((block, y), (inline, x)) => (
+                        offset(matches!(inline, PositionFourSimple::End), x, w),
+                        offset(matches!(block, PositionFourSimple::End), y, h),
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Radii of a `circle()` (equal) or `ellipse()` centered at `center`.
+    fn radial_size(&self, size: &RadialSize, center: (f64, f64), circle: bool) -> (f64, f64) {
+        let (w, h) = (self.width, self.height);
+        let (cx, cy) = center;
+        let (near_x, far_x) = (cx.abs().min((w - cx).abs()), cx.abs().max((w - cx).abs()));
+        let (near_y, far_y) = (cy.abs().min((h - cy).abs()), cy.abs().max((h - cy).abs()));
+        let reference_length = (w * w + h * h).sqrt() / std::f64::consts::SQRT_2;
+        match (size, circle) {
+            (RadialSize::RadialExtent(extent), true) => {
+                let r = match extent {
+                    RadialExtent::ClosestSide => near_x.min(near_y),
+                    RadialExtent::FarthestSide => far_x.max(far_y),
+                    RadialExtent::ClosestCorner => near_x.hypot(near_y),
+                    RadialExtent::FarthestCorner => far_x.hypot(far_y),
+                };
+                (r, r)
+            }
+            (RadialSize::RadialExtent(extent), false) => match extent {
+                RadialExtent::ClosestSide => (near_x, near_y),
+                RadialExtent::FarthestSide => (far_x, far_y),
+                // Corners keep the aspect ratio of the matching sides.
+                RadialExtent::ClosestCorner => (
+                    near_x * std::f64::consts::SQRT_2,
+                    near_y * std::f64::consts::SQRT_2,
+                ),
+                RadialExtent::FarthestCorner => (
+                    far_x * std::f64::consts::SQRT_2,
+                    far_y * std::f64::consts::SQRT_2,
+                ),
+            },
+            (RadialSize::Length(l), _) => {
+                let r = self.length(l);
+                (r, r)
+            }
+            (RadialSize::LengthPercentage(lp), true) => {
+                let r = self.resolve(lp, reference_length);
+                (r, r)
+            }
+            (RadialSize::LengthPercentage(lp), false) => (self.resolve(lp, w), self.resolve(lp, h)),
+        }
+    }
+}
+
+impl BasicShape {
+    /// Fill rule given to `polygon()` or `path()`, which decides the inside
+    /// of the geometry from [`BasicShape::to_path_shape`].
+    pub fn fill_rule(&self) -> FillRule {
+        match self {
+            BasicShape::Polygon(fill_rule, _, _) | BasicShape::Path(fill_rule, _) => {
+                fill_rule.unwrap_or_default()
+            }
+            _ => FillRule::NonZero,
+        }
+    }
+
+    /// Geometry of the shape within `reference`, the `(min, max)` corners of
+    /// the reference box. Fails only if a `path()` string cannot be parsed.
+    /// The fill rule is not part of the geometry; see [`BasicShape::fill_rule`].
+    pub fn to_path_shape(&self, reference: ((f32, f32), (f32, f32))) -> UkkoResult<PathShape> {
+        let r = Reference::new(reference);
+        let (w, h) = (r.width, r.height);
+        Ok(match self {
+            BasicShape::BasicShapeRect(rect) => {
+                let (left, top, right, bottom, radius) = match rect {
+                    BasicShapeRect::Inset(top, right, bottom, left, radius) => {
+                        // Missing values repeat like the margin shorthand.
+                        let right = right.unwrap_or(*top);
+                        let bottom = bottom.unwrap_or(*top);
+                        let left = left.unwrap_or(right);
+                        (
+                            r.resolve(&left, w),
+                            r.resolve(top, h),
+                            w - r.resolve(&right, w),
+                            h - r.resolve(&bottom, h),
+                            radius,
+                        )
+                    }
+                    BasicShapeRect::Xywh(x, y, width, height, radius) => {
+                        let (x, y) = (r.resolve(x, w), r.resolve(y, h));
+                        (
+                            x,
+                            y,
+                            x + r.resolve(width, w),
+                            y + r.resolve(height, h),
+                            radius,
+                        )
+                    }
+                    BasicShapeRect::Rect(top, right, bottom, left, radius) => {
+                        let edge = |v: &LengthPercentageAuto, basis: f64, auto: f64| match v {
+                            LengthPercentageAuto::LengthPercentage(lp) => r.resolve(lp, basis),
+                            LengthPercentageAuto::Auto => auto,
+                        };
+                        (
+                            edge(left, w, 0.),
+                            edge(top, h, 0.),
+                            edge(right, w, w),
+                            edge(bottom, h, h),
+                            radius,
+                        )
+                    }
+                };
+                let right = right.max(left);
+                let bottom = bottom.max(top);
+                shapes::rounded_rect(
+                    r.point(left, top),
+                    ((right - left) as f32, (bottom - top) as f32),
+                    r.radius(radius),
+                )
+            }
+            BasicShape::Circle(size, position) => {
+                let center = r.position(position);
+                let (rx, ry) = r.radial_size(size, center, true);
+                shapes::ellipse(r.point(center.0, center.1), (rx as f32, ry as f32))
+            }
+            BasicShape::Ellipse(size, position) => {
+                let center = r.position(position);
+                let (rx, ry) = r.radial_size(size, center, false);
+                shapes::ellipse(r.point(center.0, center.1), (rx as f32, ry as f32))
+            }
+            BasicShape::Polygon(_, round, points) => {
+                let points = points
+                    .iter()
+                    .flatten()
+                    .map(|(x, y)| r.point(r.resolve(x, w), r.resolve(y, h)))
+                    .collect::<Vec<_>>();
+                let radius = round.as_ref().map_or(0., |round| r.length(round));
+                shapes::rounded_polygon(&points, radius as f32)
+            }
+            BasicShape::Path(_, data) => PathShape::parse_from_str(data)?
+                .transform(&Transform::translate(r.x as f32, r.y as f32)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOX: ((f32, f32), (f32, f32)) = ((10., 20.), (110., 70.));
+
+    #[test]
+    fn test_basic_shape_rect() {
+        let inset = BasicShape::BasicShapeRect(BasicShapeRect::Inset(
+            LengthPercentage::Length(Length(5., None)),
+            Some(LengthPercentage::Percentage(0.1)),
+            None,
+            None,
+            None,
+        ));
+        let shape = inset.to_path_shape(BOX).unwrap();
+        assert_eq!(shape.control_bounds(), Some(((20., 25.), (100., 65.))));
+
+        let rounded = BasicShape::BasicShapeRect(BasicShapeRect::Xywh(
+            LengthPercentage::Length(Length(0., None)),
+            LengthPercentage::Length(Length(0., None)),
+            LengthPercentage::Percentage(0.5),
+            LengthPercentage::Percentage(1.),
+            Some(BorderRadius(
+                LengthPercentage::Length(Length(10., None)),
+                None,
+            )),
+        ));
+        let shape = rounded.to_path_shape(BOX).unwrap();
+        assert!(shape.contains((15., 45.), FillRule::NonZero));
+        assert!(!shape.contains((10.5, 20.5), FillRule::NonZero));
+    }
+
+    #[test]
+    fn test_basic_shape_radial_and_path() {
+        let circle = BasicShape::Circle(RadialSize::RadialExtent(RadialExtent::ClosestSide), None);
+        let shape = circle.to_path_shape(BOX).unwrap();
+        let ((x0, y0), (x1, y1)) = shape.control_bounds().unwrap();
+        assert!((x0 - 35.).abs() < 1e-3 && (x1 - 85.).abs() < 1e-3);
+        assert!((y0 - 20.).abs() < 1e-3 && (y1 - 70.).abs() < 1e-3);
+
+        let path = BasicShape::Path(None, "M 0,0 L 10,10".to_string());
+        let shape = path.to_path_shape(BOX).unwrap();
+        assert_eq!(shape.control_bounds(), Some(((10., 20.), (20., 30.))));
+        let relative = BasicShape::Path(None, "m 5,5 l 10,10".to_string());
+        let shape = relative.to_path_shape(BOX).unwrap();
+        assert_eq!(shape.control_bounds(), Some(((15., 25.), (25., 35.))));
+    }
+
+    #[test]
+    fn test_basic_shape_polygon() {
+        let corner = |x: f64, y: f64| {
+            (
+                LengthPercentage::Percentage(x),
+                LengthPercentage::Percentage(y),
+            )
+        };
+        let polygon = BasicShape::Polygon(
+            Some(FillRule::EvenOdd),
+            Some(Length(10., None)),
+            Some(vec![
+                corner(0., 0.),
+                corner(1., 0.),
+                corner(1., 1.),
+                corner(0., 1.),
+            ]),
+        );
+        assert_eq!(polygon.fill_rule(), FillRule::EvenOdd);
+        let shape = polygon.to_path_shape(BOX).unwrap();
+        assert!(shape.contains((60., 45.), FillRule::EvenOdd));
+        assert!(shape.contains((15., 25.), FillRule::EvenOdd));
+        for point in [(11., 21.), (109., 21.), (109., 69.), (11., 69.)] {
+            assert!(!shape.contains(point, FillRule::EvenOdd), "{:?}", point);
+        }
+        let path = BasicShape::Path(None, "M 0,0 L 10,10".to_string());
+        assert_eq!(path.fill_rule(), FillRule::NonZero);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{UkkoError, UkkoResult};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::ops::Mul;
+use std::str::FromStr;
+
+/// A 2D affine transform `[a c e; b d f; 0 0 1]`, as used by the `transform` attribute.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        a: 1.,
+        b: 0.,
+        c: 0.,
+        d: 1.,
+        e: 0.,
+        f: 0.,
+    };
+
+    pub fn new(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    pub fn translate(tx: f32, ty: f32) -> Self {
+        Self::new(1., 0., 0., 1., tx, ty)
+    }
+
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Self::new(sx, 0., 0., sy, 0., 0.)
+    }
+
+    /// Rotation by `angle` degrees around the origin.
+    pub fn rotate(angle: f32) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Self::new(cos, sin, -sin, cos, 0., 0.)
+    }
+
+    pub fn skew_x(angle: f32) -> Self {
+        Self::new(1., 0., angle.to_radians().tan(), 1., 0., 0.)
+    }
+
+    pub fn skew_y(angle: f32) -> Self {
+        Self::new(1., angle.to_radians().tan(), 0., 1., 0., 0.)
+    }
+
+    /// Returns the transform that applies `self` first and `other` second.
+    pub fn then(&self, other: &Transform) -> Transform {
+        *other * *self
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.a * self.d - self.b * self.c
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    pub fn inverse(&self) -> Option<Transform> {
+        let det = self.determinant();
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+        Some(Self::new(
+            self.d / det,
+            -self.b / det,
+            -self.c / det,
+            self.a / det,
+            (self.c * self.f - self.d * self.e) / det,
+            (self.b * self.e - self.a * self.f) / det,
+        ))
+    }
+
+    pub fn apply(&self, point: (f32, f32)) -> (f32, f32) {
+        (
+            self.a * point.0 + self.c * point.1 + self.e,
+            self.b * point.0 + self.d * point.1 + self.f,
+        )
+    }
+
+    /// Applies the linear part only, ignoring translation.
+    pub fn apply_vector(&self, vector: (f32, f32)) -> (f32, f32) {
+        (
+            self.a * vector.0 + self.c * vector.1,
+            self.b * vector.0 + self.d * vector.1,
+        )
+    }
+
+    /// Geometric mean of the scale factors, used to scale lengths such as stroke widths.
+    pub fn mean_scale(&self) -> f32 {
+        self.determinant().abs().sqrt()
+    }
+
+    fn from_function(name: &str, args: &[f32]) -> UkkoResult<Self> {
+        let arg = |i: usize| {
+            args.get(i)
+                .copied()
+                .ok_or(UkkoError::parse("Not enough arguments."))
+        };
+        Ok(match (name, args.len()) {
+            ("matrix", 6) => Self::new(args[0], args[1], args[2], args[3], args[4], args[5]),
+            ("translate", 1 | 2) => Self::translate(arg(0)?, arg(1).unwrap_or(0.)),
+            ("scale", 1 | 2) => Self::scale(arg(0)?, arg(1).unwrap_or(arg(0)?)),
+            ("rotate", 1) => Self::rotate(arg(0)?),
+            ("rotate", 3) => {
+                Self::translate(arg(1)?, arg(2)?)
+                    * Self::rotate(arg(0)?)
+                    * Self::translate(-arg(1)?, -arg(2)?)
+            }
+            ("skewX", 1) => Self::skew_x(arg(0)?),
+            ("skewY", 1) => Self::skew_y(arg(0)?),
+            _ => return Err(UkkoError::parse(format!("Invalid transform: {}", name))),
+        })
+    }
+}
+
+impl Mul for Transform {
+    type Output = Transform;
+
+    /// Matrix product; `(lhs * rhs).apply(p) == lhs.apply(rhs.apply(p))`.
+    fn mul(self, rhs: Transform) -> Self::Output {
+        Transform::new(
+            self.a * rhs.a + self.c * rhs.b,
+            self.b * rhs.a + self.d * rhs.b,
+            self.a * rhs.c + self.c * rhs.d,
+            self.b * rhs.c + self.d * rhs.d,
+            self.a * rhs.e + self.c * rhs.f + self.e,
+            self.b * rhs.e + self.d * rhs.f + self.f,
+        )
+    }
+}
+
+impl FromStr for Transform {
+    type Err = UkkoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result = Self::IDENTITY;
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            let open = rest
+                .find('(')
+                .ok_or(UkkoError::parse("Missing '(' in transform."))?;
+            let close = rest[open..]
+                .find(')')
+                .map(|close| open + close)
+                .ok_or(UkkoError::parse("Missing ')' in transform."))?;
+            let name = rest[..open].trim().trim_start_matches(',').trim();
+            let args = rest[open + 1..close]
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|a| !a.is_empty())
+                .map(|a| a.parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()?;
+            result = result * Self::from_function(name, &args)?;
+            rest = rest[close + 1..].trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        }
+        Ok(result)
+    }
+}
+
+impl Display for Transform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "matrix({} {} {} {} {} {})",
+            self.a, self.b, self.c, self.d, self.e, self.f
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let transform: Transform = "translate(5, 5) scale(2)".parse().unwrap();
+        assert_eq!(transform.apply((1., 1.)), (7., 7.));
+        for malformed in [")(", "translate)(", "rotate(45", "skewX 10)"] {
+            assert!(malformed.parse::<Transform>().is_err());
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Filter primitives computed on premultiplied RGBA pixel buffers.
+
+pub mod blend;
+pub mod blur;
+pub mod color_matrix;
+pub mod component_transfer;
+pub mod composite;
+pub mod convolve;
+pub mod displacement;
+pub mod lighting;
+pub mod morphology;
+pub mod turbulence;
+
+use crate::elements::attribute::{ColorInterpolationFilter, EdgeMode};
+use crate::render::raster::Canvas;
+
+/// Pixel at `(x, y)`, extending the image beyond its edges as `edge_mode` says.
+pub(crate) fn sample(input: &Canvas, x: isize, y: isize, edge_mode: EdgeMode) -> [f32; 4] {
+    let (width, height) = (input.width as isize, input.height as isize);
+    if width == 0 || height == 0 {
+        return [0.; 4];
+    }
+    let (x, y) = match edge_mode {
+        EdgeMode::Duplicate => (x.clamp(0, width - 1), y.clamp(0, height - 1)),
+        EdgeMode::Wrap => (x.rem_euclid(width), y.rem_euclid(height)),
+        EdgeMode::None => {
+            if x < 0 || y < 0 || x >= width || y >= height {
+                return [0.; 4];
+            }
+            (x, y)
+        }
+    };
+    input.data[(y * width + x) as usize]
+}
+
+/// Converts a premultiplied pixel to straight alpha.
+pub(crate) fn unpremultiply(pixel: [f32; 4]) -> [f32; 4] {
+    match pixel[3] > 0. {
+        true => [
+            pixel[0] / pixel[3],
+            pixel[1] / pixel[3],
+            pixel[2] / pixel[3],
+            pixel[3],
+        ],
+        false => [0.; 4],
+    }
+}
+
+pub(crate) fn premultiply(pixel: [f32; 4]) -> [f32; 4] {
+    [
+        pixel[0] * pixel[3],
+        pixel[1] * pixel[3],
+        pixel[2] * pixel[3],
+        pixel[3],
+    ]
+}
+
+/// Converts an sRGB component to linear light.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    match c <= 0.04045 {
+        true => c / 12.92,
+        false => ((c + 0.055) / 1.055).powf(2.4),
+    }
+}
+
+/// Converts a linear light component to sRGB.
+pub fn linear_to_srgb(c: f32) -> f32 {
+    match c <= 0.0031308 {
+        true => c * 12.92,
+        false => 1.055 * c.powf(1. / 2.4) - 0.055,
+    }
+}
+
+/// Combines the pixels of `a` and `b` with `f` over the union of both
+/// images, both anchored at the origin; pixels beyond an image are
+/// transparent.
+pub(crate) fn combine(
+    a: &Canvas,
+    b: &Canvas,
+    f: impl Fn([f32; 4], [f32; 4]) -> [f32; 4],
+) -> Canvas {
+    let mut output = Canvas::new(a.width.max(b.width), a.height.max(b.height));
+    for y in 0..output.height {
+        for x in 0..output.width {
+            let (x, y) = (x as isize, y as isize);
+            output.data[y as usize * output.width + x as usize] = f(
+                sample(a, x, y, EdgeMode::None),
+                sample(b, x, y, EdgeMode::None),
+            );
+        }
+    }
+    output
+}
+
+/// Applies `f` to the straight alpha pixels of `input`, with the color
+/// channels in the color space of `color_interpolation`. `Auto` uses sRGB.
+pub(crate) fn map_colors(
+    input: &Canvas,
+    color_interpolation: ColorInterpolationFilter,
+    f: impl Fn([f32; 4]) -> [f32; 4],
+) -> Canvas {
+    let linear = color_interpolation == ColorInterpolationFilter::LinearRgb;
+    let mut output = input.clone();
+    for pixel in &mut output.data {
+        let mut color = unpremultiply(*pixel);
+        if linear {
+            for c in &mut color[..3] {
+                *c = srgb_to_linear(*c);
+            }
+        }
+        let mut color = f(color).map(|c| c.clamp(0., 1.));
+        if linear {
+            for c in &mut color[..3] {
+                *c = linear_to_srgb(*c);
+            }
+        }
+        *pixel = premultiply(color);
+    }
+    output
+}
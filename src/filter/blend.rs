@@ -0,0 +1,195 @@
+use crate::elements::attribute::BlendMode;
+use crate::filter::{combine, unpremultiply};
+use crate::render::raster::Canvas;
+
+type Rgb = [f32; 3];
+
+fn hard_light(backdrop: f32, source: f32) -> f32 {
+    match source <= 0.5 {
+        true => backdrop * 2. * source,
+        false => {
+            let source = 2. * source - 1.;
+            backdrop + source - backdrop * source
+        }
+    }
+}
+
+fn soft_light(backdrop: f32, source: f32) -> f32 {
+    if source <= 0.5 {
+        return backdrop - (1. - 2. * source) * backdrop * (1. - backdrop);
+    }
+    let d = match backdrop <= 0.25 {
+        true => ((16. * backdrop - 12.) * backdrop + 4.) * backdrop,
+        false => backdrop.sqrt(),
+    };
+    backdrop + (2. * source - 1.) * (d - backdrop)
+}
+
+/// Blend function of a separable mode for one channel.
+fn separable(mode: BlendMode, backdrop: f32, source: f32) -> f32 {
+    match mode {
+        BlendMode::Multiply => backdrop * source,
+        BlendMode::Screen => backdrop + source - backdrop * source,
+        BlendMode::Overlay => hard_light(source, backdrop),
+        BlendMode::Darken => backdrop.min(source),
+        BlendMode::Lighten => backdrop.max(source),
+        BlendMode::ColorDodge if backdrop == 0. => 0.,
+        BlendMode::ColorDodge if source == 1. => 1.,
+        BlendMode::ColorDodge => (backdrop / (1. - source)).min(1.),
+        BlendMode::ColorBurn if backdrop == 1. => 1.,
+        BlendMode::ColorBurn if source == 0. => 0.,
+        BlendMode::ColorBurn => 1. - ((1. - backdrop) / source).min(1.),
+        BlendMode::HardLight => hard_light(backdrop, source),
+        BlendMode::SoftLight => soft_light(backdrop, source),
+        BlendMode::Difference => (backdrop - source).abs(),
+        BlendMode::Exclusion => backdrop + source - 2. * backdrop * source,
+        _ => source,
+    }
+}
+
+fn luminosity(c: Rgb) -> f32 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn clip_color(c: Rgb) -> Rgb {
+    let l = luminosity(c);
+    let min = c[0].min(c[1]).min(c[2]);
+    let max = c[0].max(c[1]).max(c[2]);
+    c.map(|v| {
+        let mut v = v;
+        if min < 0. {
+            v = l + (v - l) * l / (l - min);
+        }
+        if max > 1. {
+            v = l + (v - l) * (1. - l) / (max - l);
+        }
+        v
+    })
+}
+
+fn set_luminosity(c: Rgb, l: f32) -> Rgb {
+    let d = l - luminosity(c);
+    clip_color(c.map(|v| v + d))
+}
+
+fn saturation(c: Rgb) -> f32 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_saturation(c: Rgb, s: f32) -> Rgb {
+    let min = c[0].min(c[1]).min(c[2]);
+    let max = c[0].max(c[1]).max(c[2]);
+    match max > min {
+        true => c.map(|v| (v - min) * s / (max - min)),
+        false => [0.; 3],
+    }
+}
+
+/// Blend function of `mode` on straight alpha colors.
+pub fn blend_color(mode: BlendMode, backdrop: Rgb, source: Rgb) -> Rgb {
+    match mode {
+        BlendMode::Normal => source,
+        BlendMode::Hue => set_luminosity(
+            set_saturation(source, saturation(backdrop)),
+            luminosity(backdrop),
+        ),
+        BlendMode::Saturation => set_luminosity(
+            set_saturation(backdrop, saturation(source)),
+            luminosity(backdrop),
+        ),
+        BlendMode::Color => set_luminosity(source, luminosity(backdrop)),
+        BlendMode::Luminosity => set_luminosity(backdrop, luminosity(source)),
+        _ => [0, 1, 2].map(|c| separable(mode, backdrop[c], source[c])),
+    }
+}
+
+/// Blends the premultiplied pixel `source` onto `backdrop` and composites
+/// the result with source-over.
+pub fn blend_pixel(source: [f32; 4], backdrop: [f32; 4], mode: BlendMode) -> [f32; 4] {
+    let (sa, ba) = (source[3], backdrop[3]);
+    let (s, b) = (unpremultiply(source), unpremultiply(backdrop));
+    let mixed = blend_color(mode, [b[0], b[1], b[2]], [s[0], s[1], s[2]]);
+    let mut pixel = [0., 0., 0., sa + ba - sa * ba];
+    for c in 0..3 {
+        pixel[c] = source[c] * (1. - ba) + backdrop[c] * (1. - sa) + sa * ba * mixed[c];
+    }
+    pixel.map(|v| v.clamp(0., 1.))
+}
+
+/// `feBlend` and `mix-blend-mode`: blends `source` (`in`) onto `backdrop`
+/// (`in2`). Images of different sizes are blended over their union.
+pub fn blend(source: &Canvas, backdrop: &Canvas, mode: BlendMode) -> Canvas {
+    combine(source, backdrop, |s, b| blend_pixel(s, b, mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_separable_modes() {
+        let (backdrop, source) = ([0.25, 0.5, 0.75], [0.5, 0.5, 0.5]);
+        assert_eq!(
+            blend_color(BlendMode::Multiply, backdrop, source),
+            [0.125, 0.25, 0.375]
+        );
+        assert_eq!(
+            blend_color(BlendMode::Screen, backdrop, source),
+            [0.625, 0.75, 0.875]
+        );
+        assert_eq!(
+            blend_color(BlendMode::Darken, backdrop, source),
+            [0.25, 0.5, 0.5]
+        );
+        assert_eq!(
+            blend_color(BlendMode::ColorDodge, [0., 0.5, 0.5], [0.5, 1., 0.75]),
+            [0., 1., 1.]
+        );
+        assert_eq!(
+            blend_color(BlendMode::ColorBurn, [1., 0.5, 0.5], [0.5, 0., 1.]),
+            [1., 0., 0.5]
+        );
+        // Overlay is hard light with the layers swapped.
+        assert_eq!(
+            blend_color(BlendMode::Overlay, backdrop, [0.9, 0.1, 0.4]),
+            blend_color(BlendMode::HardLight, [0.9, 0.1, 0.4], backdrop)
+        );
+        // Every mode leaves a mid gray source over a mid gray backdrop in range.
+        for mode in BlendMode::ALL {
+            let gray = blend_color(mode, [0.5; 3], [0.5; 3]);
+            assert!(gray.iter().all(|c| (0. ..=1.).contains(c)), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_non_separable_modes() {
+        let (red, gray) = ([1., 0., 0.], [0.5, 0.5, 0.5]);
+        // A gray source keeps the luminosity of red but has no hue.
+        let color = blend_color(BlendMode::Color, red, gray);
+        assert!((luminosity(color) - luminosity(red)).abs() < 1e-6);
+        assert_eq!(
+            saturation(blend_color(BlendMode::Saturation, red, gray)),
+            0.
+        );
+        let luminous = blend_color(BlendMode::Luminosity, red, gray);
+        assert!((luminosity(luminous) - 0.5).abs() < 1e-6);
+        assert!(luminous[0] > luminous[1] && luminous[1] == luminous[2]);
+    }
+
+    #[test]
+    fn test_blend_pixels() {
+        let source = [0.25, 0.25, 0.25, 0.5];
+        // Without a backdrop the source is composited unchanged.
+        assert_eq!(blend_pixel(source, [0.; 4], BlendMode::Multiply), source);
+        let white = [1.; 4];
+        assert_eq!(
+            blend_pixel(source, white, BlendMode::Multiply),
+            [0.75, 0.75, 0.75, 1.]
+        );
+        assert_eq!(
+            blend_pixel(source, white, BlendMode::Normal),
+            [0.75, 0.75, 0.75, 1.]
+        );
+        assert_eq!(blend_pixel(source, white, BlendMode::Screen), [1.; 4]);
+    }
+}
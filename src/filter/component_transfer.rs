@@ -0,0 +1,123 @@
+use crate::elements::attribute::{
+    Amplitude, ColorInterpolationFilter, Exponent, Intercept, Offset, Slope, TableValues,
+};
+use crate::filter::map_colors;
+use crate::render::raster::Canvas;
+
+/// Transfer function of one channel in `feComponentTransfer`.
+#[derive(Clone, Debug, Default)]
+pub enum TransferFunction {
+    #[default]
+    Identity,
+    /// Piecewise linear interpolation between the values.
+    Table(TableValues),
+    /// Step function through the values.
+    Discrete(TableValues),
+    Linear {
+        slope: Slope,
+        intercept: Intercept,
+    },
+    Gamma {
+        amplitude: Amplitude,
+        exponent: Exponent,
+        offset: Offset,
+    },
+}
+
+impl TransferFunction {
+    /// Value of the `type` attribute.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TransferFunction::Identity => "identity",
+            TransferFunction::Table(_) => "table",
+            TransferFunction::Discrete(_) => "discrete",
+            TransferFunction::Linear { .. } => "linear",
+            TransferFunction::Gamma { .. } => "gamma",
+        }
+    }
+
+    /// Maps a component between 0 and 1. Tables without values act as the
+    /// identity, as the spec requires.
+    pub fn apply(&self, c: f32) -> f32 {
+        let c = c as f64;
+        let value = match self {
+            TransferFunction::Identity => c,
+            TransferFunction::Table(TableValues(values)) if !values.is_empty() => {
+                let n = values.len() - 1;
+                let k = ((c * n as f64).floor() as usize).min(n);
+                match k == n {
+                    true => values[n],
+                    false => values[k] + (c * n as f64 - k as f64) * (values[k + 1] - values[k]),
+                }
+            }
+            TransferFunction::Discrete(TableValues(values)) if !values.is_empty() => {
+                let n = values.len();
+                values[((c * n as f64).floor() as usize).min(n - 1)]
+            }
+            TransferFunction::Table(_) | TransferFunction::Discrete(_) => c,
+            TransferFunction::Linear { slope, intercept } => slope.0 * c + intercept.0,
+            TransferFunction::Gamma {
+                amplitude,
+                exponent,
+                offset,
+            } => amplitude.0 * c.powf(exponent.0) + offset.0,
+        };
+        value as f32
+    }
+}
+
+/// `feComponentTransfer`: remaps each of the R, G, B and A channels through
+/// its own function.
+pub fn component_transfer(
+    input: &Canvas,
+    functions: &[TransferFunction; 4],
+    color_interpolation: ColorInterpolationFilter,
+) -> Canvas {
+    map_colors(input, color_interpolation, |pixel| {
+        [0, 1, 2, 3].map(|i| functions[i].apply(pixel[i]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_functions() {
+        let table = TransferFunction::Table(TableValues(vec![0., 1., 0.5]));
+        assert_eq!(table.apply(0.25), 0.5);
+        assert_eq!(table.apply(0.75), 0.75);
+        assert_eq!(table.apply(1.), 0.5);
+        let discrete = TransferFunction::Discrete(TableValues(vec![0.2, 0.8]));
+        assert_eq!((discrete.apply(0.49), discrete.apply(0.5)), (0.2, 0.8));
+        assert_eq!(discrete.apply(1.), 0.8);
+        assert_eq!(TransferFunction::Table(TableValues(vec![])).apply(0.3), 0.3);
+        let gamma = TransferFunction::Gamma {
+            amplitude: Amplitude(2.),
+            exponent: Exponent(2.),
+            offset: Offset(0.1),
+        };
+        assert!((gamma.apply(0.5) - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_color_space() {
+        let mut input = Canvas::new(1, 1);
+        input.data[0] = [0.25, 0.25, 0.25, 0.5];
+        let halve = TransferFunction::Linear {
+            slope: Slope(0.5),
+            intercept: Intercept(0.),
+        };
+        let functions = [
+            halve.clone(),
+            halve.clone(),
+            halve,
+            TransferFunction::Identity,
+        ];
+        let srgb = component_transfer(&input, &functions, ColorInterpolationFilter::Srgb);
+        assert_eq!(srgb.data[0], [0.125, 0.125, 0.125, 0.5]);
+        // Halving linear light keeps more of the sRGB value.
+        let linear = component_transfer(&input, &functions, ColorInterpolationFilter::LinearRgb);
+        assert!((linear.data[0][0] / 0.5 - 0.3608).abs() < 1e-3);
+    }
+}
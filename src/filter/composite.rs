@@ -0,0 +1,94 @@
+use crate::elements::attribute::CompositeOperator;
+use crate::filter::combine;
+use crate::render::raster::Canvas;
+
+/// Composites the premultiplied pixel `source` onto `destination`.
+pub fn composite_pixel(
+    source: [f32; 4],
+    destination: [f32; 4],
+    operator: CompositeOperator,
+) -> [f32; 4] {
+    let (sa, da) = (source[3], destination[3]);
+    // Porter-Duff operators as the fraction of source and destination kept.
+    let (fs, fd) = match operator {
+        CompositeOperator::Over => (1., 1. - sa),
+        CompositeOperator::In => (da, 0.),
+        CompositeOperator::Out => (1. - da, 0.),
+        CompositeOperator::Atop => (da, 1. - sa),
+        CompositeOperator::Xor => (1. - da, 1. - sa),
+        CompositeOperator::Lighter => (1., 1.),
+        CompositeOperator::Arithmetic(k1, k2, k3, k4) => {
+            let (k1, k2, k3, k4) = (k1 as f32, k2 as f32, k3 as f32, k4 as f32);
+            let alpha = (k1 * sa * da + k2 * sa + k3 * da + k4).clamp(0., 1.);
+            let mut pixel = [0., 0., 0., alpha];
+            for c in 0..3 {
+                let value =
+                    k1 * source[c] * destination[c] + k2 * source[c] + k3 * destination[c] + k4;
+                // Keep the result a valid premultiplied color.
+                pixel[c] = value.clamp(0., alpha);
+            }
+            return pixel;
+        }
+    };
+    [0, 1, 2, 3].map(|c| (source[c] * fs + destination[c] * fd).min(1.))
+}
+
+/// `feComposite`: combines `source` (`in`) with `destination` (`in2`).
+/// Images of different sizes are composited over their union.
+pub fn composite(source: &Canvas, destination: &Canvas, operator: CompositeOperator) -> Canvas {
+    combine(source, destination, |s, d| composite_pixel(s, d, operator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operators() {
+        let red = [0.5, 0., 0., 0.5];
+        let blue = [0., 0., 1., 1.];
+        let clear = [0.; 4];
+        assert_eq!(
+            composite_pixel(red, blue, CompositeOperator::Over),
+            [0.5, 0., 0.5, 1.]
+        );
+        assert_eq!(composite_pixel(red, clear, CompositeOperator::In), clear);
+        assert_eq!(composite_pixel(red, clear, CompositeOperator::Out), red);
+        assert_eq!(
+            composite_pixel(red, blue, CompositeOperator::Atop),
+            [0.5, 0., 0.5, 1.]
+        );
+        assert_eq!(
+            composite_pixel(red, blue, CompositeOperator::Xor),
+            [0., 0., 0.5, 0.5]
+        );
+        assert_eq!(
+            composite_pixel(red, blue, CompositeOperator::Lighter),
+            [0.5, 0., 1., 1.]
+        );
+        // k2 and k3 of 0.5 average the images, k4 adds a constant.
+        let average = CompositeOperator::Arithmetic(0., 0.5, 0.5, 0.);
+        assert_eq!(composite_pixel(red, blue, average), [0.25, 0., 0.5, 0.75]);
+        let brighten = CompositeOperator::Arithmetic(0., 1., 0., 0.5);
+        assert_eq!(composite_pixel(red, clear, brighten), [1., 0.5, 0.5, 1.]);
+    }
+
+    #[test]
+    fn test_different_sizes() {
+        let mut source = Canvas::new(2, 1);
+        source.data = vec![[0.5, 0., 0., 0.5]; 2];
+        let mut destination = Canvas::new(1, 2);
+        destination.data = vec![[0., 0., 1., 1.]; 2];
+        let output = composite(&source, &destination, CompositeOperator::Over);
+        assert_eq!((output.width, output.height), (2, 2));
+        assert_eq!(
+            output.data,
+            [
+                [0.5, 0., 0.5, 1.],
+                [0.5, 0., 0., 0.5],
+                [0., 0., 1., 1.],
+                [0.; 4]
+            ]
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use crate::elements::attribute::{Attr, Bias, Divisor, EdgeMode};
+use crate::filter::{premultiply, sample, unpremultiply};
+use crate::render::raster::Canvas;
+use crate::{UkkoError, UkkoResult};
+
+/// `feConvolveMatrix`: combines each pixel with its neighbours through a kernel.
+#[derive(Clone, Debug)]
+pub struct ConvolveMatrix {
+    order: (usize, usize),
+    kernel: Vec<f64>,
+    divisor: Option<Divisor>,
+    bias: Bias,
+    target: Option<(usize, usize)>,
+    edge_mode: EdgeMode,
+    preserve_alpha: bool,
+}
+
+impl ConvolveMatrix {
+    /// `kernel` holds `order.0` columns by `order.1` rows, row by row.
+    pub fn new(order: (usize, usize), kernel: Vec<f64>) -> UkkoResult<Self> {
+        if order.0 == 0 || order.1 == 0 {
+            return Err(UkkoError::parse("Kernel order must be positive."));
+        }
+        if kernel.len() != order.0 * order.1 {
+            return Err(UkkoError::parse(format!(
+                "Kernel of order {}x{} needs {} values, got {}.",
+                order.0,
+                order.1,
+                order.0 * order.1,
+                kernel.len()
+            )));
+        }
+        Ok(Self {
+            order,
+            kernel,
+            divisor: None,
+            bias: Bias(0.),
+            target: None,
+            edge_mode: EdgeMode::Duplicate,
+            preserve_alpha: false,
+        })
+    }
+
+    /// Divisor of the kernel sum; by default the sum of the kernel values, or
+    /// 1 if they add up to 0. Zero is rejected as the spec requires.
+    pub fn with_divisor(mut self, divisor: Divisor) -> UkkoResult<Self> {
+        if divisor.0 == 0. {
+            return Err(UkkoError::parse("Divisor must not be zero."));
+        }
+        self.divisor = Some(divisor);
+        Ok(self)
+    }
+
+    pub fn with_bias(mut self, bias: Bias) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// Position of the output pixel within the kernel; centered by default.
+    pub fn with_target(mut self, target: (usize, usize)) -> UkkoResult<Self> {
+        if target.0 >= self.order.0 || target.1 >= self.order.1 {
+            return Err(UkkoError::parse("Target must lie within the kernel."));
+        }
+        self.target = Some(target);
+        Ok(self)
+    }
+
+    pub fn with_edge_mode(mut self, edge_mode: EdgeMode) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    /// Convolves only the color channels, keeping the alpha of the input.
+    pub fn with_preserve_alpha(mut self, preserve_alpha: bool) -> Self {
+        self.preserve_alpha = preserve_alpha;
+        self
+    }
+
+    pub fn divisor(&self) -> f64 {
+        match self.divisor {
+            Some(divisor) => divisor.0,
+            None => {
+                let sum = self.kernel.iter().sum::<f64>();
+                if sum == 0. {
+                    1.
+                } else {
+                    sum
+                }
+            }
+        }
+    }
+
+    pub fn target(&self) -> (usize, usize) {
+        self.target.unwrap_or((self.order.0 / 2, self.order.1 / 2))
+    }
+
+    /// Attributes of the matching `feConvolveMatrix` element.
+    pub(crate) fn attributes(&self) -> Vec<(String, String)> {
+        let kernel = self
+            .kernel
+            .iter()
+            .map(|k| k.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut attributes = vec![
+            (
+                "order".to_string(),
+                format!("{} {}", self.order.0, self.order.1),
+            ),
+            ("kernelMatrix".to_string(), kernel),
+            (self.bias.name(), self.bias.value()),
+            (self.edge_mode.name(), self.edge_mode.value()),
+            ("preserveAlpha".to_string(), self.preserve_alpha.to_string()),
+        ];
+        if let Some(divisor) = self.divisor {
+            attributes.push((divisor.name(), divisor.value()));
+        }
+        if let Some((x, y)) = self.target {
+            attributes.push(("targetX".to_string(), x.to_string()));
+            attributes.push(("targetY".to_string(), y.to_string()));
+        }
+        attributes
+    }
+
+    pub fn apply(&self, input: &Canvas) -> Canvas {
+        let (order_x, order_y) = self.order;
+        let (target_x, target_y) = self.target();
+        let (divisor, bias) = (self.divisor(), self.bias.0);
+        let mut output = Canvas::new(input.width, input.height);
+        for y in 0..input.height {
+            for x in 0..input.width {
+                let mut sum = [0f64; 4];
+                for i in 0..order_y {
+                    for j in 0..order_x {
+                        let mut pixel = sample(
+                            input,
+                            (x + j) as isize - target_x as isize,
+                            (y + i) as isize - target_y as isize,
+                            self.edge_mode,
+                        );
+                        if self.preserve_alpha {
+                            pixel = unpremultiply(pixel);
+                        }
+                        // The kernel is applied rotated by 180 degrees.
+                        let weight = self.kernel[(order_y - i - 1) * order_x + (order_x - j - 1)];
+                        for c in 0..4 {
+                            sum[c] += pixel[c] as f64 * weight;
+                        }
+                    }
+                }
+                let index = y * input.width + x;
+                output.data[index] = if self.preserve_alpha {
+                    let alpha = input.data[index][3];
+                    let channel = |c: usize| (sum[c] / divisor + bias).clamp(0., 1.) as f32;
+                    premultiply([channel(0), channel(1), channel(2), alpha])
+                } else {
+                    let alpha = (sum[3] / divisor + bias).clamp(0., 1.);
+                    let channel =
+                        |c: usize| (sum[c] / divisor + bias * alpha).clamp(0., alpha) as f32;
+                    [channel(0), channel(1), channel(2), alpha as f32]
+                };
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas(pixels: &[[f32; 4]], width: usize) -> Canvas {
+        Canvas {
+            width,
+            height: pixels.len() / width,
+            data: pixels.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_convolve() {
+        let (red, clear) = ([1., 0., 0., 1.], [0.; 4]);
+        let input = canvas(&[clear, red, clear, clear], 4);
+        // Shifts to the right: the kernel is rotated before it is applied.
+        let shift = ConvolveMatrix::new((3, 1), vec![0., 0., 1.])
+            .unwrap()
+            .with_edge_mode(EdgeMode::None);
+        assert_eq!(shift.apply(&input).data, vec![clear, clear, red, clear]);
+        let average = ConvolveMatrix::new((3, 1), vec![1., 1., 1.]).unwrap();
+        assert_eq!(average.divisor(), 3.);
+        let result = average.apply(&input);
+        assert!((result.data[0][3] - 1. / 3.).abs() < 1e-6);
+        let wrapped = shift
+            .with_edge_mode(EdgeMode::Wrap)
+            .apply(&canvas(&[red, clear], 2));
+        assert_eq!(wrapped.data, vec![clear, red]);
+        assert!(ConvolveMatrix::new((2, 2), vec![1.; 3]).is_err());
+    }
+
+    #[test]
+    fn test_preserve_alpha_and_bias() {
+        let input = canvas(&[[0.25, 0., 0., 0.5]], 1);
+        let identity = ConvolveMatrix::new((1, 1), vec![1.])
+            .unwrap()
+            .with_bias(Bias(0.25));
+        assert_eq!(
+            identity.apply(&input).data[0],
+            [0.4375, 0.1875, 0.1875, 0.75]
+        );
+        let preserved = identity.with_preserve_alpha(true).apply(&input);
+        assert_eq!(preserved.data[0], [0.375, 0.125, 0.125, 0.5]);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::elements::attribute::{MorphologyOperator, Radius};
+use crate::render::raster::Canvas;
+
+/// Minimum or maximum of each channel over a line of `2 * radius + 1`
+/// pixels, clipped to the image.
+fn extreme_line(line: &[[f32; 4]], radius: usize, dilate: bool) -> Vec<[f32; 4]> {
+    (0..line.len())
+        .map(|i| {
+            let window = &line[i.saturating_sub(radius)..(i + radius + 1).min(line.len())];
+            let mut result = window[0];
+            for pixel in &window[1..] {
+                for c in 0..4 {
+                    result[c] = match dilate {
+                        true => result[c].max(pixel[c]),
+                        false => result[c].min(pixel[c]),
+                    };
+                }
+            }
+            result
+        })
+        .collect()
+}
+
+/// `feMorphology`: thins (erode) or fattens (dilate) the input over a
+/// rectangle reaching `radius` pixels to each side. A radius that is not
+/// positive on either axis disables the effect.
+pub fn morphology(input: &Canvas, operator: MorphologyOperator, radius: Radius) -> Canvas {
+    let (rx, ry) = (radius.0, radius.1.unwrap_or(radius.0));
+    if rx <= 0. || ry <= 0. {
+        return input.clone();
+    }
+    let (rx, ry) = (rx.floor() as usize, ry.floor() as usize);
+    let dilate = operator == MorphologyOperator::Dilate;
+    let (width, height) = (input.width, input.height);
+    let mut output = input.clone();
+    if width == 0 || height == 0 {
+        return output;
+    }
+    // The rectangle is separable into a horizontal and a vertical pass.
+    for y in 0..height {
+        let row = &mut output.data[y * width..(y + 1) * width];
+        let result = extreme_line(row, rx, dilate);
+        row.copy_from_slice(&result);
+    }
+    for x in 0..width {
+        let column = (0..height)
+            .map(|y| output.data[y * width + x])
+            .collect::<Vec<_>>();
+        for (y, pixel) in extreme_line(&column, ry, dilate).into_iter().enumerate() {
+            output.data[y * width + x] = pixel;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erode_dilate() {
+        let mut input = Canvas::new(5, 5);
+        input.data[2 * 5 + 2] = [1.; 4];
+        let dilated = morphology(&input, MorphologyOperator::Dilate, Radius(1., None));
+        let covered = dilated.data.iter().filter(|p| p[3] == 1.).count();
+        assert_eq!(covered, 9);
+        let eroded = morphology(&dilated, MorphologyOperator::Erode, Radius(1., None));
+        assert_eq!(eroded, input);
+        let flat = morphology(&input, MorphologyOperator::Dilate, Radius(2., Some(0.)));
+        assert_eq!(flat, input);
+    }
+}
@@ -1,13 +1,24 @@
 pub mod elements;
-
+pub mod filter;
+pub mod render;
+pub mod serializer;
+pub mod writer;
+
+use crate::elements::generic::GenericElement;
+use crate::elements::path::PathShape;
+use crate::writer::SvgWriter;
 use hex::FromHex;
+use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::io;
 use std::num::{ParseFloatError, ParseIntError};
 use std::ops::Add;
 use std::str::FromStr;
+use xml::namespace::Namespace;
+use xml::reader::{EventReader, XmlEvent};
 pub use xmltree;
-use xmltree::{AttributeMap, Element, XMLNode};
+use xmltree::{Element, ParserConfig, XMLNode};
 pub fn gen_svg(node: XMLNode, view_box: (f32, f32)) -> Element {
     let mut top_element = Element::new("svg");
     top_element.attributes.insert(
@@ -73,33 +84,247 @@ impl From<ParseIntError> for UkkoError {
     }
 }
 
-pub trait SvgElement {
-    fn children(&self) -> Vec<Box<dyn SvgElement>>;
-    fn attributes(&self) -> HashMap<String, String>;
-    fn attributes_mut(&mut self) -> &mut HashMap<String, String>;
+/// Attributes of an element, keyed by their qualified name.
+pub type AttributeMap = HashMap<String, String>;
+
+/// A child of an element.
+pub enum Node {
+    Element(Box<dyn SvgElement>),
+    Text(String),
+    Comment(String),
+    /// Character data written verbatim, as used by `<style>` and `<script>`.
+    CData(String),
+    /// Target and data of a processing instruction.
+    ProcessingInstruction(String, Option<String>),
+}
+
+impl Node {
+    /// Parses a document into its top level nodes. Elements are read into
+    /// [`GenericElement`]s.
+    pub fn parse(text: &str) -> UkkoResult<Vec<Node>> {
+        let config = ParserConfig::new()
+            .ignore_comments(false)
+            .cdata_to_characters(false);
+        let mut reader = EventReader::new_with_config(text.as_bytes(), config);
+        let mut nodes = vec![];
+        let mut open: Vec<(GenericElement, Namespace)> = vec![];
+        loop {
+            let node = match reader.next().map_err(UkkoError::parse)? {
+                XmlEvent::StartElement {
+                    name,
+                    attributes,
+                    namespace,
+                } => {
+                    let parent = open.last().map(|(_, namespace)| namespace);
+                    let element =
+                        GenericElement::from_start_tag(&name, &attributes, &namespace, parent);
+                    open.push((element, namespace));
+                    continue;
+                }
+                XmlEvent::EndElement { .. } => match open.pop() {
+                    Some((element, _)) => element.into(),
+                    None => continue,
+                },
+                XmlEvent::Characters(text) => Node::Text(text),
+                XmlEvent::CData(text) => Node::CData(text),
+                XmlEvent::Comment(text) => Node::Comment(text),
+                XmlEvent::ProcessingInstruction { name, data } => {
+                    Node::ProcessingInstruction(name, data)
+                }
+                XmlEvent::EndDocument => break,
+                _ => continue,
+            };
+            match open.last_mut() {
+                Some((parent, _)) => parent.children_mut().push(node),
+                None => nodes.push(node),
+            }
+        }
+        Ok(nodes)
+    }
+
+    pub fn from_xml_node(node: &XMLNode) -> Self {
+        match node {
+            XMLNode::Element(element) => GenericElement::from_xml_element(element).into(),
+            XMLNode::Text(text) => Node::Text(text.clone()),
+            XMLNode::Comment(text) => Node::Comment(text.clone()),
+            XMLNode::CData(text) => Node::CData(text.clone()),
+            XMLNode::ProcessingInstruction(target, data) => {
+                Node::ProcessingInstruction(target.clone(), data.clone())
+            }
+        }
+    }
+
+    pub fn as_element(&self) -> Option<&dyn SvgElement> {
+        match self {
+            Node::Element(element) => Some(element.as_ref()),
+            _ => None,
+        }
+    }
+
+    pub fn as_element_mut(&mut self) -> Option<&mut dyn SvgElement> {
+        match self {
+            Node::Element(element) => Some(element.as_mut()),
+            _ => None,
+        }
+    }
+
+    /// The element, if it is of type `E`.
+    pub fn downcast_ref<E: SvgElement + 'static>(&self) -> Option<&E> {
+        self.as_element()?.as_any().downcast_ref()
+    }
+
+    /// Text of a text or CDATA node.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Node::Text(text) | Node::CData(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn to_xml_node(&self) -> XMLNode {
+        match self {
+            Node::Element(element) => element.to_xml_node(),
+            Node::Text(text) => XMLNode::Text(text.clone()),
+            Node::Comment(text) => XMLNode::Comment(text.clone()),
+            Node::CData(text) => XMLNode::CData(text.clone()),
+            Node::ProcessingInstruction(target, data) => {
+                XMLNode::ProcessingInstruction(target.clone(), data.clone())
+            }
+        }
+    }
+
+    pub fn write_to(&self, writer: &mut SvgWriter) -> io::Result<()> {
+        match self {
+            Node::Element(element) => element.write_to(writer),
+            Node::Text(text) => writer.text(text),
+            Node::Comment(text) => writer.comment(text),
+            Node::CData(text) => writer.cdata(text),
+            Node::ProcessingInstruction(target, data) => {
+                writer.processing_instruction(target, data.as_deref())
+            }
+        }
+    }
+}
+
+impl<E: SvgElement + 'static> From<E> for Node {
+    fn from(element: E) -> Self {
+        Node::Element(Box::new(element))
+    }
+}
+
+/// Access to the concrete type of an element, implemented for every type.
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// An SVG element. Attributes and children are borrowed from the element, so
+/// a document can be walked without copying it, and since elements are
+/// `Send + Sync` subtrees can be processed on other threads. Typed elements
+/// are recovered from a [`Node`] with [`Node::downcast_ref`].
+pub trait SvgElement: AsAny + Send + Sync {
+    /// Attributes set on the element, without the ones derived from its
+    /// typed fields.
+    fn attributes(&self) -> &AttributeMap;
+    fn attributes_mut(&mut self) -> &mut AttributeMap;
+
+    /// Attributes backed by typed fields, such as the geometry of a shape,
+    /// with `None` for unset ones. They replace the same attributes of
+    /// [`attributes`](Self::attributes) when the element is written, so a
+    /// written element always matches the one that is rendered and picked.
+    fn typed_attributes(&self) -> Vec<(String, Option<String>)> {
+        vec![]
+    }
+
+    /// All attributes as they are written.
+    fn all_attributes(&self) -> AttributeMap {
+        let typed = self.typed_attributes();
+        merge_attributes(self.attributes(), &typed)
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+
+    fn children(&self) -> &[Node] {
+        &[]
+    }
 
     fn with_attribute(&mut self, key: String, value: String) {
         self.attributes_mut().insert(key, value);
     }
-    fn name(&self) -> String;
+    fn name(&self) -> &str;
+
+    /// Text content, written before the children.
     fn value(&self) -> Option<&String> {
         None
     }
 
+    /// Concatenated text of the value and all text and CDATA children.
+    fn text_content(&self) -> String {
+        let mut text = self.value().cloned().unwrap_or_default();
+        for child in self.children() {
+            match child {
+                Node::Element(element) => text.push_str(&element.text_content()),
+                _ => text.push_str(child.as_text().unwrap_or_default()),
+            }
+        }
+        text
+    }
+
+    /// Geometry of the element as a path, if it has any.
+    fn path_shape(&self) -> Option<PathShape> {
+        None
+    }
+
     fn to_xml_node(&self) -> XMLNode {
         XMLNode::Element(Element {
             prefix: None,
             namespace: None,
             namespaces: None,
-            name: self.name(),
-            attributes: AttributeMap::from(self.attributes()),
+            name: self.name().to_string(),
+            attributes: self.all_attributes(),
             children: self
-                .children()
-                .iter()
-                .map(|c| c.to_xml_node())
+                .value()
+                .map(|v| XMLNode::Text(v.clone()))
+                .into_iter()
+                .chain(self.children().iter().map(|c| c.to_xml_node()))
                 .collect::<Vec<_>>(),
         })
     }
+
+    /// Streams the element and its children to `writer`.
+    fn write_to(&self, writer: &mut SvgWriter) -> io::Result<()> {
+        writer.start_element(self.name())?;
+        let typed = self.typed_attributes();
+        writer.attributes(merge_attributes(self.attributes(), &typed))?;
+        if let Some(value) = self.value() {
+            writer.text(value)?;
+        }
+        for child in self.children() {
+            child.write_to(writer)?;
+        }
+        writer.end_element()
+    }
+}
+
+/// Borrows the untyped `attributes` that no typed attribute overrides,
+/// followed by the set `typed` ones.
+fn merge_attributes<'a>(
+    attributes: &'a AttributeMap,
+    typed: &'a [(String, Option<String>)],
+) -> impl Iterator<Item = (&'a String, &'a String)> {
+    attributes
+        .iter()
+        .filter(|(name, _)| !typed.iter().any(|(typed, _)| typed == *name))
+        .chain(
+            typed
+                .iter()
+                .filter_map(|(name, value)| value.as_ref().map(|value| (name, value))),
+        )
 }
 
 pub trait Attribute: Sized {
@@ -132,6 +357,7 @@ fn to_hex_int(val: f32) -> u8 {
     (255. * val).floor() as u8
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
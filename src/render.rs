@@ -0,0 +1,477 @@
+pub mod compare;
+pub mod png;
+pub mod raster;
+
+use crate::elements::attribute::{StrokeLinecap, StrokeLinejoin};
+use crate::elements::value::parse::{parse_color, parse_number};
+use crate::elements::value::transform::Transform;
+use crate::elements::value::FillRule;
+use crate::render::raster::{rasterize, Canvas};
+use crate::serializer::parse_style;
+use crate::{Color, SvgElement, UkkoResult};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+/// Flattening tolerance in device pixels.
+const TOLERANCE: f32 = 0.1;
+
+/// Containers whose contents are only drawn when referenced from elsewhere.
+const NON_RENDERED: [&str; 9] = [
+    "clipPath",
+    "defs",
+    "filter",
+    "linearGradient",
+    "marker",
+    "mask",
+    "pattern",
+    "radialGradient",
+    "symbol",
+];
+
+/// 8-bit RGBA image with non-premultiplied alpha.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Transparent black image.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        (data.len() == width as usize * height as usize * 4).then_some(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Pixels as consecutive RGBA bytes, row by row.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        self.data[i..i + 4].copy_from_slice(&rgba);
+    }
+
+    /// Premultiplied pixels, as filter primitives take them.
+    pub fn to_canvas(&self) -> Canvas {
+        let data = self
+            .data
+            .chunks(4)
+            .map(|p| {
+                let alpha = p[3] as f32 / 255.;
+                [
+                    p[0] as f32 / 255. * alpha,
+                    p[1] as f32 / 255. * alpha,
+                    p[2] as f32 / 255. * alpha,
+                    alpha,
+                ]
+            })
+            .collect();
+        Canvas {
+            width: self.width as usize,
+            height: self.height as usize,
+            data,
+        }
+    }
+
+    /// Fails if the image is empty.
+    pub fn to_png(&self) -> UkkoResult<Vec<u8>> {
+        png::encode(self.width, self.height, &self.data)
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let png = self
+            .to_png()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("{:?}", e)))?;
+        std::fs::write(path, png)
+    }
+
+    pub fn from_canvas(canvas: &Canvas) -> Self {
+        let mut data = Vec::with_capacity(canvas.data.len() * 4);
+        for pixel in &canvas.data {
+            let alpha = pixel[3].clamp(0., 1.);
+            let straight = |c: f32| match alpha > 0. {
+                true => (c / alpha).clamp(0., 1.),
+                false => 0.,
+            };
+            data.extend([
+                (straight(pixel[0]) * 255.).round() as u8,
+                (straight(pixel[1]) * 255.).round() as u8,
+                (straight(pixel[2]) * 255.).round() as u8,
+                (alpha * 255.).round() as u8,
+            ]);
+        }
+        Self {
+            width: canvas.width as u32,
+            height: canvas.height as u32,
+            data,
+        }
+    }
+}
+
+/// Maps the `viewBox` of `root`, if any, onto a `width` by `height` image
+/// according to its `preserveAspectRatio`.
+pub fn view_box_transform(root: &dyn SvgElement, width: f32, height: f32) -> Transform {
+    let attributes = root.attributes();
+    let Some(view_box) = attributes.get("viewBox").and_then(|v| {
+        let numbers = v
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .map(|p| p.parse::<f32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        (numbers.len() == 4 && numbers[2] > 0. && numbers[3] > 0.).then_some(numbers)
+    }) else {
+        return Transform::IDENTITY;
+    };
+    let (min_x, min_y, vw, vh) = (view_box[0], view_box[1], view_box[2], view_box[3]);
+    let (mut sx, mut sy) = (width / vw, height / vh);
+    let aspect = attributes
+        .get("preserveAspectRatio")
+        .map(|a| a.split_whitespace().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let align = aspect.first().copied().unwrap_or("xMidYMid");
+    let (mut tx, mut ty) = (0., 0.);
+    if align != "none" {
+        let scale = match aspect.get(1) {
+            Some(&"slice") => sx.max(sy),
+            _ => sx.min(sy),
+        };
+        (sx, sy) = (scale, scale);
+        // `align` reads like `xMidYMax`, so axes are told apart by case.
+        let offset = |axis: &str, free: f32| {
+            if align.contains(&format!("{}Mid", axis)) {
+                free / 2.
+            } else if align.contains(&format!("{}Max", axis)) {
+                free
+            } else {
+                0.
+            }
+        };
+        tx = offset("x", width - vw * scale);
+        ty = offset("Y", height - vh * scale);
+    }
+    Transform::new(sx, 0., 0., sy, tx - min_x * sx, ty - min_y * sy)
+}
+
+/// Image size of a document: the `width` and `height` of `root`, falling back
+/// to the size of its `viewBox` and then to 100 by 100.
+pub fn document_size(root: &dyn SvgElement) -> (u32, u32) {
+    let attributes = root.attributes();
+    let view_box = attributes
+        .get("viewBox")
+        .map(|v| {
+            v.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|p| !p.is_empty())
+                .filter_map(|p| p.parse::<f32>().ok())
+                .collect::<Vec<_>>()
+        })
+        .filter(|v| v.len() == 4);
+    let size = |name: &str, index: usize| {
+        attributes
+            .get(name)
+            .and_then(|v| parse_number(v))
+            .or_else(|| view_box.as_ref().map(|v| v[index]))
+            .map_or(100, |v| v.ceil().max(0.) as u32)
+    };
+    (size("width", 2), size("height", 3))
+}
+
+/// Presentation properties inherited down the element tree.
+#[derive(Copy, Clone, Debug)]
+struct RenderState {
+    transform: Transform,
+    color: (Color, f32),
+    fill: Option<(Color, f32)>,
+    fill_opacity: f32,
+    fill_rule: FillRule,
+    stroke: Option<(Color, f32)>,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    linecap: StrokeLinecap,
+    linejoin: StrokeLinejoin,
+    miterlimit: f32,
+    visible: bool,
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        Self {
+            transform: Transform::IDENTITY,
+            color: (Color::BLACK, 1.),
+            fill: Some((Color::BLACK, 1.)),
+            fill_opacity: 1.,
+            fill_rule: FillRule::NonZero,
+            stroke: None,
+            stroke_opacity: 1.,
+            stroke_width: 1.,
+            linecap: StrokeLinecap::Butt,
+            linejoin: StrokeLinejoin::Miter,
+            miterlimit: 4.,
+            visible: true,
+        }
+    }
+}
+
+impl RenderState {
+    /// Applies the properties of an element, where declarations of `style`
+    /// override presentation attributes.
+    fn apply(mut self, properties: &HashMap<String, String>) -> Self {
+        let get = |name: &str| properties.get(name).map(|v| v.trim());
+        if let Some(transform) = get("transform").and_then(|t| t.parse().ok()) {
+            self.transform = self.transform * transform;
+        }
+        if let Some(color) = get("color").and_then(|c| parse_color(c, self.color)) {
+            self.color = color;
+        }
+        // Paint servers are not supported, so `url()` paints use their
+        // fallback color, or no paint without one.
+        let paint = |value: &str, current| {
+            let value = match value.strip_prefix("url(") {
+                Some(rest) => rest.split_once(')').map_or("", |(_, f)| f.trim()),
+                None => value,
+            };
+            match value {
+                "" | "none" => Some(None),
+                value => parse_color(value, current).map(Some),
+            }
+        };
+        if let Some(fill) = get("fill").and_then(|f| paint(f, self.color)) {
+            self.fill = fill;
+        }
+        if let Some(stroke) = get("stroke").and_then(|s| paint(s, self.color)) {
+            self.stroke = stroke;
+        }
+        if let Some(opacity) = get("fill-opacity").and_then(parse_number) {
+            self.fill_opacity = opacity.clamp(0., 1.);
+        }
+        if let Some(opacity) = get("stroke-opacity").and_then(parse_number) {
+            self.stroke_opacity = opacity.clamp(0., 1.);
+        }
+        if let Some(fill_rule) = get("fill-rule").and_then(|f| f.parse().ok()) {
+            self.fill_rule = fill_rule;
+        }
+        if let Some(width) = get("stroke-width").and_then(parse_number) {
+            self.stroke_width = width;
+        }
+        if let Some(limit) = get("stroke-miterlimit").and_then(parse_number) {
+            self.miterlimit = limit;
+        }
+        match get("visibility") {
+            Some("visible") => self.visible = true,
+            Some("hidden" | "collapse") => self.visible = false,
+            _ => {}
+        }
+        match get("stroke-linecap") {
+            Some("butt") => self.linecap = StrokeLinecap::Butt,
+            Some("round") => self.linecap = StrokeLinecap::Round,
+            Some("square") => self.linecap = StrokeLinecap::Square,
+            _ => {}
+        }
+        match get("stroke-linejoin") {
+            Some("miter") => self.linejoin = StrokeLinejoin::Miter,
+            Some("miter-clip") => self.linejoin = StrokeLinejoin::MiterClip,
+            Some("round") => self.linejoin = StrokeLinejoin::Round,
+            Some("bevel") => self.linejoin = StrokeLinejoin::Bevel,
+            Some("arcs") => self.linejoin = StrokeLinejoin::Arcs,
+            _ => {}
+        }
+        self
+    }
+}
+
+fn premultiplied((color, alpha): (Color, f32), opacity: f32) -> [f32; 4] {
+    let a = alpha * opacity;
+    [color.r * a, color.g * a, color.b * a, a]
+}
+
+/// Properties of an element with the declarations of its `style` attribute
+/// taking precedence.
+fn properties(element: &dyn SvgElement) -> HashMap<String, String> {
+    let mut properties = element.attributes().clone();
+    if let Some(style) = properties.remove("style") {
+        properties.extend(parse_style(&style));
+    }
+    properties
+}
+
+fn render_element(element: &dyn SvgElement, state: RenderState, canvas: &mut Canvas) {
+    if NON_RENDERED.contains(&element.name()) {
+        return;
+    }
+    let properties = properties(element);
+    if properties.get("display").map(|d| d.trim()) == Some("none") {
+        return;
+    }
+    let state = state.apply(&properties);
+    let opacity = properties
+        .get("opacity")
+        .and_then(|o| parse_number(o))
+        .map_or(1., |o| o.clamp(0., 1.));
+    if opacity <= 0. {
+        return;
+    }
+    let mut layer = (opacity < 1.).then(|| Canvas::new(canvas.width, canvas.height));
+    let target = layer.as_mut().unwrap_or(canvas);
+    if let (Some(shape), true) = (element.path_shape(), state.visible) {
+        let (width, height) = (target.width, target.height);
+        if let Some(fill) = state.fill {
+            let polylines = shape.transform(&state.transform).flatten(TOLERANCE);
+            let mask = rasterize(&polylines, state.fill_rule, width, height);
+            target.fill_mask(&mask, premultiplied(fill, state.fill_opacity));
+        }
+        if let (Some(stroke), true) = (state.stroke, state.stroke_width > 0.) {
+            // Flatten finely enough that the outline stays within the
+            // tolerance once scaled to the device.
+            let scale = state.transform.mean_scale();
+            let outline = shape.stroke_to_path_with(
+                state.stroke_width,
+                state.linecap,
+                state.linejoin,
+                state.miterlimit,
+                match scale > 0. {
+                    true => TOLERANCE / scale,
+                    false => TOLERANCE,
+                },
+            );
+            let polylines = outline.transform(&state.transform).flatten(TOLERANCE);
+            let mask = rasterize(&polylines, FillRule::NonZero, width, height);
+            target.fill_mask(&mask, premultiplied(stroke, state.stroke_opacity));
+        }
+    }
+    for child in element.children() {
+        if let Some(child) = child.as_element() {
+            render_element(child, state, target);
+        }
+    }
+    if let Some(layer) = layer {
+        canvas.draw_layer(&layer, opacity);
+    }
+}
+
+/// Renders `root` and its descendants into a `width` by `height` image,
+/// mapping the `viewBox` of `root` onto the image.
+pub fn render(root: &dyn SvgElement, width: u32, height: u32) -> Image {
+    let mut canvas = Canvas::new(width as usize, height as usize);
+    let state = RenderState {
+        transform: view_box_transform(root, width as f32, height as f32),
+        ..Default::default()
+    };
+    render_element(root, state, &mut canvas);
+    Image::from_canvas(&canvas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::circle::Circle;
+    use crate::elements::generic::GenericElement;
+    use crate::elements::group::Group;
+    use crate::elements::rect::Rect;
+
+    #[test]
+    fn test_render_document() {
+        let mut rect = Rect::new((0., 0.), (10., 5.));
+        rect.with_attribute("fill".to_string(), "#0000ff".to_string());
+        let mut circle = Circle::new((15., 5.), 3.);
+        circle.with_attribute("fill".to_string(), "none".to_string());
+        circle.with_attribute(
+            "style".to_string(),
+            "stroke: red; stroke-width: 2".to_string(),
+        );
+        let mut group = Group::new().with_child(circle);
+        group.with_attribute("opacity".to_string(), "0.5".to_string());
+        let mut svg = GenericElement::new("svg")
+            .with_child(rect)
+            .with_child(group);
+        svg.with_attribute("viewBox".to_string(), "0 0 20 10".to_string());
+
+        let image = render(&svg, 40, 20);
+        assert_eq!(image.pixel(2, 2), [0, 0, 255, 255]);
+        assert_eq!(image.pixel(2, 15), [0, 0, 0, 0]);
+        // Stroke ring at radius 3 in user units, drawn at half opacity.
+        assert_eq!(image.pixel(30 + 6, 10), [255, 0, 0, 128]);
+        assert_eq!(image.pixel(30, 10)[3], 0);
+        assert!(image.to_png().unwrap().len() > 40 * 20 * 4);
+    }
+
+    #[test]
+    fn test_skip_non_rendered() {
+        let mut gradient_fill = Rect::new((0., 0.), (10., 10.));
+        gradient_fill.with_attribute("fill".to_string(), "url(#paint)".to_string());
+        let mut fallback_fill = Rect::new((10., 0.), (10., 10.));
+        fallback_fill.with_attribute("fill".to_string(), "url(#paint) red".to_string());
+        let svg = GenericElement::new("svg")
+            .with_child(GenericElement::new("defs").with_child(Rect::new((0., 0.), (20., 20.))))
+            .with_child(gradient_fill)
+            .with_child(fallback_fill);
+
+        let image = render(&svg, 20, 20);
+        assert_eq!(image.pixel(5, 5), [0, 0, 0, 0]);
+        assert_eq!(image.pixel(15, 5), [255, 0, 0, 255]);
+        assert_eq!(image.pixel(5, 15), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_inherited_visibility() {
+        let hidden = Rect::new((0., 0.), (10., 10.));
+        let mut shown = Rect::new((10., 0.), (10., 10.));
+        shown.with_attribute("visibility".to_string(), "visible".to_string());
+        let mut group = Group::new().with_child(hidden).with_child(shown);
+        group.with_attribute("visibility".to_string(), "hidden".to_string());
+        let svg = GenericElement::new("svg").with_child(group);
+
+        let image = render(&svg, 20, 10);
+        assert_eq!(image.pixel(5, 5), [0, 0, 0, 0]);
+        assert_eq!(image.pixel(15, 5), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_view_box_alignment() {
+        let mut svg = GenericElement::new("svg");
+        svg.with_attribute("viewBox".to_string(), "0 0 10 10".to_string());
+        let meet = view_box_transform(&svg, 40., 20.);
+        assert_eq!(meet.apply((0., 0.)), (10., 0.));
+        svg.with_attribute(
+            "preserveAspectRatio".to_string(),
+            "xMaxYMax slice".to_string(),
+        );
+        let slice = view_box_transform(&svg, 40., 20.);
+        assert_eq!(slice.apply((10., 10.)), (40., 20.));
+        svg.with_attribute("preserveAspectRatio".to_string(), "none".to_string());
+        assert_eq!(
+            view_box_transform(&svg, 40., 20.).apply((10., 10.)),
+            (40., 20.)
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::render::{document_size, render, Image};
+use crate::SvgElement;
+
+/// Result of comparing two images pixel by pixel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    /// The first image faded to gray, with differing pixels in red, brighter
+    /// the larger the difference.
+    pub diff: Image,
+    pub mismatched_pixels: usize,
+    /// Share of differing pixels, between 0 and 100.
+    pub mismatch_percentage: f32,
+    /// Smallest and largest coordinates of differing pixels.
+    pub changed_bounds: Option<((u32, u32), (u32, u32))>,
+}
+
+impl Comparison {
+    pub fn is_match(&self) -> bool {
+        self.mismatched_pixels == 0
+    }
+}
+
+/// Compares images of the same size. Pixels differ if any channel, with
+/// colors weighted by alpha, differs by more than `tolerance` (0 to 1).
+///
+/// # Panics
+/// If the images differ in size.
+pub fn compare_images(a: &Image, b: &Image, tolerance: f32) -> Comparison {
+    assert_eq!(
+        (a.width(), a.height()),
+        (b.width(), b.height()),
+        "images to compare must have the same size"
+    );
+    let mut diff = Image::new(a.width(), a.height());
+    let mut mismatched_pixels = 0;
+    let mut bounds: Option<((u32, u32), (u32, u32))> = None;
+    for y in 0..a.height() {
+        for x in 0..a.width() {
+            let (pa, pb) = (a.pixel(x, y), b.pixel(x, y));
+            // Blend onto white so that fully transparent pixels of any color match.
+            let blended = |p: [u8; 4], c: usize| {
+                let alpha = p[3] as f32 / 255.;
+                (p[c] as f32 / 255.) * alpha + (1. - alpha)
+            };
+            let difference = (0..3)
+                .map(|c| (blended(pa, c) - blended(pb, c)).abs())
+                .chain([(pa[3] as f32 - pb[3] as f32).abs() / 255.])
+                .fold(0f32, f32::max);
+            if difference > tolerance {
+                mismatched_pixels += 1;
+                bounds = Some(match bounds {
+                    Some((min, max)) => {
+                        ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y)))
+                    }
+                    None => ((x, y), (x, y)),
+                });
+                let intensity = (127. + difference * 128.).round() as u8;
+                diff.set_pixel(x, y, [intensity, 0, 0, 255]);
+            } else {
+                let gray = (0..3).map(|c| blended(pa, c)).sum::<f32>() / 3.;
+                let faded = ((0.9 + gray * 0.1) * 255.).round() as u8;
+                diff.set_pixel(x, y, [faded, faded, faded, 255]);
+            }
+        }
+    }
+    let total = a.width() as usize * a.height() as usize;
+    Comparison {
+        diff,
+        mismatched_pixels,
+        mismatch_percentage: match total {
+            0 => 0.,
+            _ => mismatched_pixels as f32 * 100. / total as f32,
+        },
+        changed_bounds: bounds,
+    }
+}
+
+/// Renders both trees at the size of `a` and compares the results.
+pub fn compare(a: &dyn SvgElement, b: &dyn SvgElement, tolerance: f32) -> Comparison {
+    let (width, height) = document_size(a);
+    compare_images(
+        &render(a, width, height),
+        &render(b, width, height),
+        tolerance,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::generic::GenericElement;
+    use crate::elements::rect::Rect;
+
+    fn document(x: f32) -> GenericElement {
+        let mut svg = GenericElement::new("svg").with_child(Rect::new((x, 2.), (4., 4.)));
+        svg.with_attribute("width".to_string(), "20".to_string());
+        svg.with_attribute("height".to_string(), "10".to_string());
+        svg
+    }
+
+    #[test]
+    fn test_compare() {
+        assert!(compare(&document(2.), &document(2.), 0.).is_match());
+        let moved = compare(&document(2.), &document(3.), 0.1);
+        assert_eq!(moved.mismatched_pixels, 8);
+        assert_eq!(moved.mismatch_percentage, 4.);
+        assert_eq!(moved.changed_bounds, Some(((2, 2), (6, 5))));
+        assert_eq!(moved.diff.pixel(2, 2), [255, 0, 0, 255]);
+        assert_eq!(moved.diff.pixel(0, 0), [255, 255, 255, 255]);
+        let shifted = compare(&document(2.), &document(2.25), 0.3);
+        assert!(shifted.is_match());
+    }
+}
@@ -0,0 +1,124 @@
+//! Minimal PNG encoder. Image data is stored in uncompressed deflate blocks,
+//! which keeps the encoder small at the cost of file size.
+
+use crate::{UkkoError, UkkoResult};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+/// Largest payload of a stored deflate block.
+const MAX_BLOCK: usize = 0xffff;
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// zlib stream of stored blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let blocks = data.chunks(MAX_BLOCK).collect::<Vec<_>>();
+    if blocks.is_empty() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        out.push((i + 1 == blocks.len()) as u8);
+        let len = block.len() as u16;
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(*block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+/// Encodes 8-bit non-premultiplied RGBA pixels, row by row, as a PNG file.
+/// PNG has no empty images, so a zero width or height is an error, as is a
+/// buffer whose length does not match the size.
+pub fn encode(width: u32, height: u32, rgba: &[u8]) -> UkkoResult<Vec<u8>> {
+    if width == 0 || height == 0 {
+        return Err(UkkoError::parse(format!(
+            "Cannot encode a {}x{} image as PNG.",
+            width, height
+        )));
+    }
+    if rgba.len() != width as usize * height as usize * 4 {
+        return Err(UkkoError::parse(format!(
+            "Expected {} bytes for a {}x{} image, got {}.",
+            width as usize * height as usize * 4,
+            width,
+            height,
+            rgba.len()
+        )));
+    }
+    let mut out = SIGNATURE.to_vec();
+
+    let mut header = vec![];
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // Bit depth 8, color type RGBA, default compression, filter and no interlacing.
+    header.extend([8, 6, 0, 0, 0]);
+    chunk(&mut out, b"IHDR", &header);
+
+    let mut scanlines = Vec::with_capacity(rgba.len() + height as usize);
+    for row in rgba.chunks(width as usize * 4) {
+        scanlines.push(0);
+        scanlines.extend(row);
+    }
+    chunk(&mut out, b"IDAT", &zlib_stored(&scanlines));
+    chunk(&mut out, b"IEND", &[]);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert!(encode(0, 1, &[]).is_err());
+        assert!(encode(1, 0, &[]).is_err());
+        assert!(encode(1, 1, &[0, 0, 0]).is_err());
+        let png = encode(2, 1, &[255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[png.len() - 8..png.len() - 4], *b"IEND");
+        // Stored block holding the filter byte and 8 bytes of pixels.
+        let idat = &png[33 + 8..];
+        assert_eq!(&idat[..2], &[0x78, 0x01]);
+        assert_eq!(&idat[2..7], &[1, 9, 0, !9, 0xff]);
+        assert_eq!(&idat[7..10], &[0, 255, 0]);
+    }
+}
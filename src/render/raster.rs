@@ -0,0 +1,178 @@
+use crate::elements::path::hit::fill_rule_matches;
+use crate::elements::value::FillRule;
+
+/// Sub-scanlines per pixel row. Coverage along a scanline is exact, so this
+/// only limits the anti-aliasing of nearly horizontal edges.
+const SUBSAMPLES: usize = 16;
+
+/// Coverage of each pixel, between 0 and 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mask {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<f32>,
+}
+
+struct Edge {
+    top: (f32, f32),
+    bottom: (f32, f32),
+    /// +1 for edges going down, -1 for edges going up.
+    direction: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f32) -> f32 {
+        let t = (y - self.top.1) / (self.bottom.1 - self.top.1);
+        self.top.0 + (self.bottom.0 - self.top.0) * t
+    }
+}
+
+/// Adds the coverage of the span `x0..x1` to a row.
+fn add_span(row: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let x0 = x0.max(0.);
+    let x1 = x1.min(row.len() as f32);
+    if x1 <= x0 {
+        return;
+    }
+    let (first, last) = (x0.floor() as usize, (x1.ceil() as usize).min(row.len()));
+    for (px, value) in row.iter_mut().enumerate().take(last).skip(first) {
+        let px = px as f32;
+        *value += (x1.min(px + 1.) - x0.max(px)) * weight;
+    }
+}
+
+/// Anti-aliased coverage of the area enclosed by `polylines`, each closed
+/// implicitly, in device pixels.
+pub fn rasterize(
+    polylines: &[Vec<(f32, f32)>],
+    fill_rule: FillRule,
+    width: usize,
+    height: usize,
+) -> Mask {
+    let mut data = vec![0.; width * height];
+    let mut edges = polylines
+        .iter()
+        .filter(|p| p.len() > 1)
+        .flat_map(|p| p.iter().zip(p.iter().cycle().skip(1)))
+        .filter(|(a, b)| a.1 != b.1)
+        .map(|(&a, &b)| match a.1 < b.1 {
+            true => Edge {
+                top: a,
+                bottom: b,
+                direction: 1,
+            },
+            false => Edge {
+                top: b,
+                bottom: a,
+                direction: -1,
+            },
+        })
+        .collect::<Vec<_>>();
+    edges.sort_by(|a, b| a.top.1.total_cmp(&b.top.1));
+
+    let mut next = 0;
+    let mut active: Vec<&Edge> = vec![];
+    let mut crossings: Vec<(f32, i32)> = vec![];
+    let weight = 1. / SUBSAMPLES as f32;
+    for y in 0..height {
+        let row = &mut data[y * width..(y + 1) * width];
+        for s in 0..SUBSAMPLES {
+            let sy = y as f32 + (s as f32 + 0.5) * weight;
+            while next < edges.len() && edges[next].top.1 <= sy {
+                active.push(&edges[next]);
+                next += 1;
+            }
+            active.retain(|e| e.bottom.1 > sy);
+            crossings.clear();
+            crossings.extend(
+                active
+                    .iter()
+                    .filter(|e| e.top.1 <= sy)
+                    .map(|e| (e.x_at(sy), e.direction)),
+            );
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                if fill_rule_matches(fill_rule, winding) {
+                    add_span(row, pair[0].0, pair[1].0, weight);
+                }
+            }
+        }
+        if next >= edges.len() && active.is_empty() {
+            break;
+        }
+    }
+    for value in &mut data {
+        *value = value.min(1.);
+    }
+    Mask {
+        width,
+        height,
+        data,
+    }
+}
+
+/// Premultiplied RGBA pixels with components between 0 and 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Canvas {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![[0.; 4]; width * height],
+        }
+    }
+
+    /// Paints `color` (premultiplied) through `mask` with source-over.
+    pub fn fill_mask(&mut self, mask: &Mask, color: [f32; 4]) {
+        for (pixel, &coverage) in self.data.iter_mut().zip(&mask.data) {
+            if coverage > 0. {
+                let alpha = color[3] * coverage;
+                for c in 0..4 {
+                    pixel[c] = color[c] * coverage + pixel[c] * (1. - alpha);
+                }
+            }
+        }
+    }
+
+    /// Composites `layer` over this canvas, scaled by `opacity`.
+    pub fn draw_layer(&mut self, layer: &Canvas, opacity: f32) {
+        for (pixel, source) in self.data.iter_mut().zip(&layer.data) {
+            let alpha = source[3] * opacity;
+            for c in 0..4 {
+                pixel[c] = source[c] * opacity + pixel[c] * (1. - alpha);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rasterize_coverage() {
+        let square = vec![vec![(1., 1.), (3.5, 1.), (3.5, 3.), (1., 3.)]];
+        let mask = rasterize(&square, FillRule::NonZero, 5, 4);
+        assert_eq!(mask.data[5 + 1], 1.);
+        assert!((mask.data[5 + 3] - 0.5).abs() < 1e-6);
+        assert_eq!(mask.data[5 * 3 + 1], 0.);
+        let total = mask.data.iter().sum::<f32>();
+        assert!((total - 5.).abs() < 1e-4);
+
+        let nested = vec![
+            vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)],
+            vec![(1., 1.), (3., 1.), (3., 3.), (1., 3.)],
+        ];
+        let even_odd = rasterize(&nested, FillRule::EvenOdd, 4, 4);
+        let non_zero = rasterize(&nested, FillRule::NonZero, 4, 4);
+        assert_eq!((even_odd.data[5], non_zero.data[5]), (0., 1.));
+    }
+}
@@ -0,0 +1,576 @@
+use crate::elements::attribute::{is_presentation_attribute, PRESENTATION_ATTRIBUTES};
+use crate::SvgElement;
+use std::collections::HashMap;
+use xmltree::{Element, XMLNode};
+
+const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>";
+const DOCTYPE: &str = "<!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" \
+\"http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd\">";
+
+const GEOMETRY_ATTRIBUTES: &[&str] = &[
+    "x",
+    "y",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "r",
+    "rx",
+    "ry",
+    "fx",
+    "fy",
+    "fr",
+    "width",
+    "height",
+    "viewBox",
+    "preserveAspectRatio",
+    "d",
+    "points",
+    "pathLength",
+    "transform",
+];
+
+/// Attributes whose values are names rather than numbers.
+const NON_NUMERIC_ATTRIBUTES: &[&str] = &["id", "class", "href", "xlink:href", "version"];
+
+/// Order in which attributes of an element are written.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum AttributeOrder {
+    Alphabetical,
+    /// Namespaces, `id`, `class`, geometry, presentation attributes, then
+    /// everything else alphabetically.
+    #[default]
+    Conventional,
+    /// The listed attributes first, in the given order, then everything else
+    /// alphabetically.
+    Custom(Vec<String>),
+}
+
+impl AttributeOrder {
+    fn rank(&self, name: &str) -> (usize, usize) {
+        let position = |list: &[&str]| list.iter().position(|a| *a == name);
+        match self {
+            AttributeOrder::Alphabetical => (0, 0),
+            AttributeOrder::Conventional => {
+                if name == "xmlns" || name.starts_with("xmlns:") {
+                    (0, 0)
+                } else if name == "id" {
+                    (1, 0)
+                } else if name == "class" {
+                    (2, 0)
+                } else if let Some(i) = position(GEOMETRY_ATTRIBUTES) {
+                    (3, i)
+                } else if let Some(i) = position(PRESENTATION_ATTRIBUTES) {
+                    (4, i)
+                } else {
+                    (5, 0)
+                }
+            }
+            AttributeOrder::Custom(list) => match list.iter().position(|a| a == name) {
+                Some(i) => (0, i),
+                None => (1, 0),
+            },
+        }
+    }
+
+    /// Sorts attributes into this order; ties are broken alphabetically.
+    pub fn sort<'a>(
+        &self,
+        attributes: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) -> Vec<(&'a String, &'a String)> {
+        let mut sorted = attributes.into_iter().collect::<Vec<_>>();
+        sorted.sort_by(|a, b| {
+            self.rank(a.0)
+                .cmp(&self.rank(b.0))
+                .then_with(|| a.0.cmp(b.0))
+        });
+        sorted
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Indent {
+    /// Everything on one line without whitespace between elements.
+    #[default]
+    Compact,
+    Spaces(usize),
+    Tabs,
+}
+
+/// Whether presentation attributes are written as attributes or as
+/// declarations of the `style` attribute.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum StyleMode {
+    /// Writes attributes as they are.
+    #[default]
+    Preserve,
+    /// Moves presentation attributes into `style`. Declarations already in
+    /// `style` take precedence, as they would when rendering.
+    Style,
+    /// Expands declarations of `style` into presentation attributes. Properties
+    /// without a presentation attribute and `!important` declarations stay in
+    /// `style`.
+    Attributes,
+}
+
+impl StyleMode {
+    pub fn apply<'a>(
+        &self,
+        attributes: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) -> HashMap<String, String> {
+        let mut result = attributes
+            .into_iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<HashMap<_, _>>();
+        let declarations = result
+            .remove("style")
+            .map(|s| parse_style(&s))
+            .unwrap_or_default();
+        let declarations = match self {
+            StyleMode::Preserve => declarations,
+            StyleMode::Style => {
+                let mut moved = result
+                    .iter()
+                    .filter(|(k, _)| is_presentation_attribute(k))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .filter(|(k, _)| !declarations.iter().any(|(p, _)| p == k))
+                    .collect::<Vec<_>>();
+                moved.sort();
+                result.retain(|k, _| !is_presentation_attribute(k));
+                moved.into_iter().chain(declarations).collect()
+            }
+            StyleMode::Attributes => {
+                let (expanded, kept): (Vec<_>, Vec<_>) = declarations
+                    .into_iter()
+                    .partition(|(k, v)| is_presentation_attribute(k) && !is_important(v));
+                result.extend(expanded);
+                kept
+            }
+        };
+        if !declarations.is_empty() {
+            result.insert("style".to_string(), format_style(&declarations));
+        }
+        result
+    }
+}
+
+/// Whether a declaration value ends in `!important`, which has no attribute
+/// equivalent.
+fn is_important(value: &str) -> bool {
+    value
+        .to_ascii_lowercase()
+        .strip_suffix("important")
+        .is_some_and(|v| v.trim_end().ends_with('!'))
+}
+
+/// Splits a `style` attribute into property names and values, ignoring `;`
+/// inside quotes and parentheses such as `url(data:...)`.
+pub fn parse_style(style: &str) -> Vec<(String, String)> {
+    let mut declarations = vec![];
+    let (mut depth, mut quote, mut start) = (0, None, 0);
+    let mut push = |text: &str| {
+        if let Some((name, value)) = text.split_once(':') {
+            let (name, value) = (name.trim(), value.trim());
+            if !name.is_empty() && !value.is_empty() {
+                declarations.push((name.to_ascii_lowercase(), value.to_string()));
+            }
+        }
+    };
+    for (i, c) in style.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('(', None) => depth += 1,
+            (')', None) => depth -= 1,
+            (';', None) if depth <= 0 => {
+                push(&style[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    push(&style[start..]);
+    declarations
+}
+
+pub fn format_style(declarations: &[(String, String)]) -> String {
+    declarations
+        .iter()
+        .map(|(k, v)| format!("{}:{}", k, v))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SerializeOptions {
+    pub attribute_order: AttributeOrder,
+    pub style_mode: StyleMode,
+    pub indent: Indent,
+    pub xml_declaration: bool,
+    pub doctype: bool,
+    /// Maximum number of decimals of numbers in attribute values.
+    pub precision: Option<usize>,
+}
+
+impl SerializeOptions {
+    /// Indented output with a declaration.
+    pub fn pretty() -> Self {
+        Self {
+            indent: Indent::Spaces(2),
+            xml_declaration: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_attribute_order(mut self, order: AttributeOrder) -> Self {
+        self.attribute_order = order;
+        self
+    }
+
+    pub fn with_style_mode(mut self, style_mode: StyleMode) -> Self {
+        self.style_mode = style_mode;
+        self
+    }
+
+    pub fn with_indent(mut self, indent: Indent) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub fn with_precision(mut self, precision: Option<usize>) -> Self {
+        self.precision = precision;
+        self
+    }
+}
+
+pub(crate) fn escape(text: &str, attribute: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' if attribute => result.push_str("&quot;"),
+            '\n' if attribute => result.push_str("&#xA;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+fn format_number(number: &str, precision: usize) -> String {
+    let Ok(value) = number.parse::<f64>() else {
+        return number.to_string();
+    };
+    let decimals = number
+        .split(['e', 'E'])
+        .next()
+        .and_then(|mantissa| mantissa.split('.').nth(1))
+        .map_or(0, |d| d.len());
+    if decimals <= precision && !number.contains(['e', 'E']) {
+        return number.to_string();
+    }
+    let text = format!("{:.*}", precision, value);
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Wraps `text` in a CDATA section, splitting it where it contains `]]>`.
+pub(crate) fn cdata(text: &str) -> String {
+    format!("<![CDATA[{}]]>", text.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+/// Rounds every number in an attribute value to `precision` decimals.
+/// Numbers glued to a preceding letter are only numbers in path data.
+pub(crate) fn round_numbers(name: &str, value: &str, precision: usize) -> String {
+    if NON_NUMERIC_ATTRIBUTES.contains(&name) || name.starts_with("xmlns") {
+        return value.to_string();
+    }
+    let letters_delimit = name == "d";
+    let chars = value.char_indices().collect::<Vec<_>>();
+    let mut result = String::with_capacity(value.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        let prev = i.checked_sub(1).map(|j| chars[j].1);
+        let delimited = match prev {
+            None => true,
+            // A sign always starts a new number.
+            Some(p) if c == '-' || c == '+' => p != 'e' && p != 'E',
+            Some(p) if p.is_ascii_alphabetic() => letters_delimit,
+            Some(p) => !(p.is_ascii_digit() || p == '#' || p == '_' || p == '.'),
+        };
+        let starts_number = c.is_ascii_digit()
+            || ((c == '.' || c == '-' || c == '+')
+                && chars
+                    .get(i + 1)
+                    .is_some_and(|n| n.1.is_ascii_digit() || n.1 == '.'));
+        if !(delimited && starts_number) {
+            result.push(c);
+            i += 1;
+            continue;
+        }
+        let mut j = i + 1;
+        let mut seen_dot = c == '.';
+        while j < chars.len() {
+            let d = chars[j].1;
+            if d.is_ascii_digit() {
+                j += 1;
+            } else if d == '.' && !seen_dot {
+                seen_dot = true;
+                j += 1;
+            } else if (d == 'e' || d == 'E')
+                && !letters_delimit
+                && chars
+                    .get(j + 1)
+                    .is_some_and(|n| n.1.is_ascii_digit() || n.1 == '-')
+            {
+                j += 2;
+                while j < chars.len() && chars[j].1.is_ascii_digit() {
+                    j += 1;
+                }
+                break;
+            } else {
+                break;
+            }
+        }
+        let end = chars.get(j).map_or(value.len(), |c| c.0);
+        result.push_str(&format_number(&value[start..end], precision));
+        i = j;
+    }
+    result
+}
+
+/// Writes XML with deterministic attribute order and configurable layout.
+pub struct Serializer {
+    options: SerializeOptions,
+}
+
+impl Serializer {
+    pub fn new(options: SerializeOptions) -> Self {
+        Self { options }
+    }
+
+    pub fn options(&self) -> &SerializeOptions {
+        &self.options
+    }
+
+    pub fn serialize(&self, element: &dyn SvgElement) -> String {
+        self.serialize_node(&element.to_xml_node())
+    }
+
+    pub fn serialize_element(&self, element: &Element) -> String {
+        let mut out = self.prolog();
+        self.write_element(element, 0, false, &mut out);
+        out
+    }
+
+    pub fn serialize_node(&self, node: &XMLNode) -> String {
+        let mut out = self.prolog();
+        self.write_node(node, 0, false, &mut out);
+        out
+    }
+
+    fn prolog(&self) -> String {
+        let mut out = String::new();
+        for (enabled, line) in [
+            (self.options.xml_declaration, XML_DECLARATION),
+            (self.options.doctype, DOCTYPE),
+        ] {
+            if enabled {
+                out.push_str(line);
+                if self.options.indent != Indent::Compact {
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+
+    fn indent(&self, depth: usize, out: &mut String) {
+        match self.options.indent {
+            Indent::Compact => {}
+            Indent::Spaces(n) => out.push_str(&" ".repeat(n * depth)),
+            Indent::Tabs => out.push_str(&"\t".repeat(depth)),
+        }
+    }
+
+    fn attribute_value(&self, name: &str, value: &str) -> String {
+        match self.options.precision {
+            Some(precision) => round_numbers(name, value, precision),
+            None => value.to_string(),
+        }
+    }
+
+    /// Writes `node`; `inline` suppresses indentation inside mixed content.
+    fn write_node(&self, node: &XMLNode, depth: usize, inline: bool, out: &mut String) {
+        match node {
+            XMLNode::Element(element) => self.write_element(element, depth, inline, out),
+            XMLNode::Text(text) => out.push_str(&escape(text, false)),
+            XMLNode::CData(text) => out.push_str(&cdata(text)),
+            XMLNode::Comment(text) => {
+                out.push_str("<!--");
+                out.push_str(text);
+                out.push_str("-->");
+            }
+            XMLNode::ProcessingInstruction(target, data) => {
+                out.push_str("<?");
+                out.push_str(target);
+                if let Some(data) = data {
+                    out.push(' ');
+                    out.push_str(data);
+                }
+                out.push_str("?>");
+            }
+        }
+    }
+
+    fn write_element(&self, element: &Element, depth: usize, inline: bool, out: &mut String) {
+        let pretty = self.options.indent != Indent::Compact && !inline;
+        if pretty {
+            self.indent(depth, out);
+        }
+        out.push('<');
+        if let Some(prefix) = &element.prefix {
+            out.push_str(prefix);
+            out.push(':');
+        }
+        out.push_str(&element.name);
+        let styled;
+        let attributes = match self.options.style_mode {
+            StyleMode::Preserve => &element.attributes,
+            mode => {
+                styled = mode.apply(&element.attributes);
+                &styled
+            }
+        };
+        for (name, value) in self.options.attribute_order.sort(attributes) {
+            out.push(' ');
+            out.push_str(name);
+            out.push_str("=\"");
+            out.push_str(&escape(&self.attribute_value(name, value), true));
+            out.push('"');
+        }
+        if element.children.is_empty() {
+            out.push_str("/>");
+        } else {
+            out.push('>');
+            // Whitespace would change text content, so mixed content stays inline.
+            let inline = inline
+                || element
+                    .children
+                    .iter()
+                    .any(|c| matches!(c, XMLNode::Text(_) | XMLNode::CData(_)));
+            for child in &element.children {
+                if pretty && !inline {
+                    out.push('\n');
+                    if !matches!(child, XMLNode::Element(_)) {
+                        self.indent(depth + 1, out);
+                    }
+                }
+                self.write_node(child, depth + 1, inline, out);
+            }
+            if pretty && !inline {
+                out.push('\n');
+                self.indent(depth, out);
+            }
+            out.push_str("</");
+            if let Some(prefix) = &element.prefix {
+                out.push_str(prefix);
+                out.push(':');
+            }
+            out.push_str(&element.name);
+            out.push('>');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::path::{Path, PathShape};
+    use crate::gen_svg;
+
+    #[test]
+    fn test_attribute_order() {
+        let mut path = PathShape::parse_from_str("M 10,10 Z").unwrap().to_path();
+        path.with_attribute("stroke".to_string(), "red".to_string());
+        path.with_attribute("id".to_string(), "a".to_string());
+        path.with_attribute("fill".to_string(), "none".to_string());
+        path.with_attribute("data-x".to_string(), "1".to_string());
+        let serializer = Serializer::new(SerializeOptions::default());
+        assert_eq!(
+            serializer.serialize(&path),
+            "<path id=\"a\" d=\"M 10,10&#xA;Z\" fill=\"none\" stroke=\"red\" data-x=\"1\"/>"
+        );
+        let alphabetical = Serializer::new(
+            SerializeOptions::default().with_attribute_order(AttributeOrder::Alphabetical),
+        );
+        assert_eq!(
+            alphabetical.serialize(&path),
+            "<path d=\"M 10,10&#xA;Z\" data-x=\"1\" fill=\"none\" id=\"a\" stroke=\"red\"/>"
+        );
+    }
+
+    #[test]
+    fn test_pretty_and_precision() {
+        let mut path = PathShape::parse_from_str("M 10.12345,-10.556 Z")
+            .unwrap()
+            .to_path();
+        path.with_attribute("stroke-width".to_string(), "1.23456".to_string());
+        path.with_attribute("id".to_string(), "p1.23456".to_string());
+        let svg = gen_svg(path.to_xml_node(), (100., 100.));
+        let serializer = Serializer::new(SerializeOptions::pretty().with_precision(Some(2)));
+        assert_eq!(
+            serializer.serialize_element(&svg),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\">\n\
+             \x20 <path id=\"p1.23456\" d=\"M 10.12,-10.56&#xA;Z\" stroke-width=\"1.23\"/>\n\
+             </svg>"
+        );
+    }
+
+    #[test]
+    fn test_style_mode() {
+        let mut path = PathShape::parse_from_str("M 0,0 Z").unwrap().to_path();
+        path.with_attribute("fill".to_string(), "red".to_string());
+        path.with_attribute("stroke".to_string(), "blue".to_string());
+        path.with_attribute(
+            "style".to_string(),
+            "stroke: green; mix-blend-mode: multiply; background: url(a;b)".to_string(),
+        );
+        let serialize = |path: &Path, mode| {
+            Serializer::new(SerializeOptions::default().with_style_mode(mode)).serialize(path)
+        };
+        assert_eq!(
+            serialize(&path, StyleMode::Style),
+            "<path d=\"M 0,0&#xA;Z\" \
+             style=\"fill:red;stroke:green;mix-blend-mode:multiply;background:url(a;b)\"/>"
+        );
+        assert_eq!(
+            serialize(&path, StyleMode::Attributes),
+            "<path d=\"M 0,0&#xA;Z\" fill=\"red\" stroke=\"green\" \
+             style=\"mix-blend-mode:multiply;background:url(a;b)\"/>"
+        );
+
+        path.with_attribute(
+            "style".to_string(),
+            "stroke: green ! important; opacity: 0.5".to_string(),
+        );
+        assert_eq!(
+            serialize(&path, StyleMode::Attributes),
+            "<path d=\"M 0,0&#xA;Z\" fill=\"red\" opacity=\"0.5\" stroke=\"blue\" \
+             style=\"stroke:green ! important\"/>"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use crate::serializer::{cdata, escape, round_numbers, Indent, SerializeOptions, StyleMode};
+use crate::SvgElement;
+use std::io::{self, Write};
+use xmltree::XMLNode;
+
+struct OpenElement {
+    name: String,
+    has_children: bool,
+    /// Set once text is written, after which no whitespace may be added.
+    inline: bool,
+}
+
+/// Writes SVG directly to an output stream, one start tag, attribute, child
+/// and end tag at a time, without building a tree in memory.
+pub struct SvgWriter<'w> {
+    out: &'w mut dyn Write,
+    options: SerializeOptions,
+    stack: Vec<OpenElement>,
+    /// `true` while the start tag of the innermost element is unfinished.
+    tag_open: bool,
+    started: bool,
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+impl<'w> SvgWriter<'w> {
+    pub fn new(out: &'w mut dyn Write, options: SerializeOptions) -> Self {
+        Self {
+            out,
+            options,
+            stack: vec![],
+            tag_open: false,
+            started: false,
+        }
+    }
+
+    pub fn options(&self) -> &SerializeOptions {
+        &self.options
+    }
+
+    fn pretty(&self) -> bool {
+        self.options.indent != Indent::Compact && !self.stack.last().is_some_and(|e| e.inline)
+    }
+
+    fn newline(&mut self, depth: usize) -> io::Result<()> {
+        let indent = match self.options.indent {
+            Indent::Compact => return Ok(()),
+            Indent::Spaces(n) => " ".repeat(n * depth),
+            Indent::Tabs => "\t".repeat(depth),
+        };
+        write!(self.out, "\n{}", indent)
+    }
+
+    fn prolog(&mut self) -> io::Result<()> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+        let mut first = true;
+        if self.options.xml_declaration {
+            write!(self.out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+            first = false;
+        }
+        if self.options.doctype {
+            if !first {
+                self.newline(0)?;
+            }
+            write!(
+                self.out,
+                "<!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" \
+                 \"http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd\">"
+            )?;
+            first = false;
+        }
+        if !first {
+            self.newline(0)?;
+        }
+        Ok(())
+    }
+
+    /// Finishes the start tag of the parent and positions the next child.
+    fn begin_child(&mut self, is_element: bool) -> io::Result<()> {
+        self.prolog()?;
+        if self.tag_open {
+            self.out.write_all(b">")?;
+            self.tag_open = false;
+        }
+        let pretty = self.pretty();
+        let depth = self.stack.len();
+        if let Some(parent) = self.stack.last_mut() {
+            parent.has_children = true;
+            if !is_element {
+                parent.inline = true;
+            } else if pretty {
+                self.newline(depth)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn start_element(&mut self, name: &str) -> io::Result<()> {
+        self.begin_child(true)?;
+        write!(self.out, "<{}", name)?;
+        self.stack.push(OpenElement {
+            name: name.to_string(),
+            has_children: false,
+            inline: false,
+        });
+        self.tag_open = true;
+        Ok(())
+    }
+
+    /// Writes an attribute of the element whose start tag is still open.
+    pub fn attribute(&mut self, name: &str, value: &str) -> io::Result<()> {
+        if !self.tag_open {
+            return Err(invalid("attributes must directly follow the start tag"));
+        }
+        let value = match self.options.precision {
+            Some(precision) => round_numbers(name, value, precision),
+            None => value.to_string(),
+        };
+        write!(self.out, " {}=\"{}\"", name, escape(&value, true))
+    }
+
+    /// Writes attributes in the configured order and style mode.
+    pub fn attributes<'a>(
+        &mut self,
+        attributes: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) -> io::Result<()> {
+        if self.options.style_mode != StyleMode::Preserve {
+            let styled = self.options.style_mode.apply(attributes);
+            for (name, value) in self.options.attribute_order.sort(&styled) {
+                self.attribute(name, value)?;
+            }
+            return Ok(());
+        }
+        for (name, value) in self.options.attribute_order.sort(attributes) {
+            self.attribute(name, value)?;
+        }
+        Ok(())
+    }
+
+    pub fn text(&mut self, text: &str) -> io::Result<()> {
+        self.begin_child(false)?;
+        self.out.write_all(escape(text, false).as_bytes())
+    }
+
+    pub fn cdata(&mut self, text: &str) -> io::Result<()> {
+        self.begin_child(false)?;
+        self.out.write_all(cdata(text).as_bytes())
+    }
+
+    pub fn comment(&mut self, text: &str) -> io::Result<()> {
+        self.begin_child(true)?;
+        write!(self.out, "<!--{}-->", text)
+    }
+
+    pub fn processing_instruction(&mut self, target: &str, data: Option<&str>) -> io::Result<()> {
+        self.begin_child(true)?;
+        match data {
+            Some(data) => write!(self.out, "<?{} {}?>", target, data),
+            None => write!(self.out, "<?{}?>", target),
+        }
+    }
+
+    pub fn end_element(&mut self) -> io::Result<()> {
+        let element = self
+            .stack
+            .pop()
+            .ok_or_else(|| invalid("no element to end"))?;
+        if self.tag_open {
+            self.tag_open = false;
+            return self.out.write_all(b"/>");
+        }
+        if element.has_children && !element.inline && self.options.indent != Indent::Compact {
+            let inline_parent = self.stack.last().is_some_and(|e| e.inline);
+            if !inline_parent {
+                self.newline(self.stack.len())?;
+            }
+        }
+        write!(self.out, "</{}>", element.name)
+    }
+
+    /// Writes a node built with [`SvgElement::to_xml_node`].
+    pub fn node(&mut self, node: &XMLNode) -> io::Result<()> {
+        match node {
+            XMLNode::Element(element) => {
+                let name = match &element.prefix {
+                    Some(prefix) => format!("{}:{}", prefix, element.name),
+                    None => element.name.clone(),
+                };
+                self.start_element(&name)?;
+                self.attributes(&element.attributes)?;
+                for child in &element.children {
+                    self.node(child)?;
+                }
+                self.end_element()
+            }
+            XMLNode::Text(text) => self.text(text),
+            XMLNode::CData(text) => self.cdata(text),
+            XMLNode::Comment(text) => self.comment(text),
+            XMLNode::ProcessingInstruction(target, data) => {
+                self.processing_instruction(target, data.as_deref())
+            }
+        }
+    }
+
+    pub fn element(&mut self, element: &dyn SvgElement) -> io::Result<()> {
+        element.write_to(self)
+    }
+
+    /// Closes all open elements and flushes the output.
+    pub fn finish(mut self) -> io::Result<()> {
+        while !self.stack.is_empty() {
+            self.end_element()?;
+        }
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::path::PathShape;
+    use crate::serializer::Serializer;
+
+    #[test]
+    fn test_streaming_matches_serializer() {
+        let mut path = PathShape::parse_from_str("M 10,10 Z").unwrap().to_path();
+        path.with_attribute("fill".to_string(), "red".to_string());
+        let svg = crate::gen_svg(path.to_xml_node(), (10., 10.));
+        for options in [SerializeOptions::default(), SerializeOptions::pretty()] {
+            let mut out = vec![];
+            let mut writer = SvgWriter::new(&mut out, options.clone());
+            writer.node(&XMLNode::Element(svg.clone())).unwrap();
+            writer.finish().unwrap();
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                Serializer::new(options).serialize_element(&svg)
+            );
+        }
+    }
+
+    #[test]
+    fn test_streaming_elements() {
+        let mut out = vec![];
+        let mut writer = SvgWriter::new(&mut out, SerializeOptions::default());
+        writer.start_element("svg").unwrap();
+        writer.attribute("width", "10").unwrap();
+        for i in 0..2 {
+            let path = PathShape::parse_from_str(&format!("M {},0 Z", i))
+                .unwrap()
+                .to_path();
+            writer.element(&path).unwrap();
+        }
+        writer.start_element("text").unwrap();
+        writer.text("a < b").unwrap();
+        assert!(writer.attribute("x", "1").is_err());
+        writer.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<svg width=\"10\"><path d=\"M 0,0&#xA;Z\"/><path d=\"M 1,0&#xA;Z\"/>\
+             <text>a &lt; b</text></svg>"
+        );
+    }
+}